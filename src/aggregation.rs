@@ -0,0 +1,610 @@
+/*!
+
+This module implements aggregation of readings into fixed-length time windows, for example for
+downsampling raw readings into 1-minute or 1-hour buckets.
+
+[`Aggregator`] collects [`Reading`]s of multiple tags, and produces an [`Aggregate`] for each tag
+and window. Measurements are summarized with minimum, maximum, mean and last value, and
+unavailable measurements are ignored. Movement counter and measurement sequence number wrap
+around, so they are summarized as a sum of increments instead.
+
+```rust
+use ruuvi_sensor_protocol::{aggregation::Aggregator, Mac, Reading, SensorValues};
+# use ruuvi_sensor_protocol::ParseError;
+
+let tag = Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F]);
+let mut aggregator = Aggregator::new(60);
+
+let value = [
+    0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+    0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+];
+let values = SensorValues::from_manufacturer_specific_data(0x0499, value)?;
+
+assert_eq!(aggregator.push(tag, &Reading::new(1_653_668_027, values.clone())), None);
+let aggregate = aggregator.push(tag, &Reading::new(1_653_668_100, values)).unwrap();
+
+assert_eq!(aggregate.start, 1_653_667_980);
+assert_eq!(aggregate.end, 1_653_668_040);
+assert_eq!(aggregate.temperature.map(|temperature| temperature.mean), Some(24_300));
+# Ok::<(), ParseError>(())
+```
+
+[`Reading`]: crate::Reading
+
+*/
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::{
+    Acceleration, BatteryPotential, Humidity, Mac, MeasurementSequenceNumber, MovementCounter,
+    Pressure, Reading, Temperature, TransmitterPower,
+};
+
+/// Summary of a measurement within a window
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Statistics<T> {
+    /// smallest value
+    pub min: T,
+    /// largest value
+    pub max: T,
+    /// mean of the values rounded to the nearest integer
+    pub mean: T,
+    /// value of the latest reading
+    pub last: T,
+    /// number of readings with the measurement available
+    pub count: usize,
+}
+
+/// Summary of a wrapping counter within a window
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CounterDelta {
+    /// sum of increments since the previous reading of the tag, taking wrap-around into account
+    pub delta: u32,
+    /// value of the latest reading
+    pub last: u32,
+}
+
+/// Aggregated readings of a tag within a window
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Aggregate {
+    /// MAC address of the tag
+    pub tag: Mac,
+    /// start of the window as seconds since Unix epoch, inclusive
+    pub start: u64,
+    /// end of the window as seconds since Unix epoch, exclusive
+    pub end: u64,
+    /// number of readings in the window
+    pub count: usize,
+    /// temperature in milli-Celsius
+    pub temperature: Option<Statistics<i32>>,
+    /// relative humidity in parts per million
+    pub humidity: Option<Statistics<u32>>,
+    /// pressure in pascals
+    pub pressure: Option<Statistics<u32>>,
+    /// each component of acceleration in milli-G
+    pub acceleration: Option<[Statistics<i16>; 3]>,
+    /// battery potential in milli-volts
+    pub battery_potential: Option<Statistics<u16>>,
+    /// transmitter power in dBm
+    pub tx_power: Option<Statistics<i8>>,
+    /// received signal strength in dBm
+    pub rssi: Option<Statistics<i16>>,
+    /// movement counter
+    pub movement_counter: Option<CounterDelta>,
+    /// measurement sequence number
+    pub measurement_sequence_number: Option<CounterDelta>,
+}
+
+/// Number of distinct values of wrapping counters, after which the counters start again from
+/// zero
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CounterRanges {
+    /// number of distinct values of movement counter
+    pub movement_counter: u32,
+    /// number of distinct values of measurement sequence number
+    pub measurement_sequence_number: u32,
+}
+
+impl Default for CounterRanges {
+    /// Returns the ranges used by data format 5, where the largest value of each counter is
+    /// reserved for unavailable value.
+    fn default() -> Self {
+        Self {
+            movement_counter: 0xFF,
+            measurement_sequence_number: 0xFFFF,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Accumulator<T> {
+    min: T,
+    max: T,
+    sum: i64,
+    last: T,
+    count: usize,
+}
+
+impl<T: Copy + Ord + Into<i64> + TryFrom<i64>> Accumulator<T> {
+    fn push(accumulator: &mut Option<Self>, value: Option<T>) {
+        let Some(value) = value else {
+            return;
+        };
+
+        if let Some(accumulator) = accumulator {
+            accumulator.min = accumulator.min.min(value);
+            accumulator.max = accumulator.max.max(value);
+            accumulator.sum += value.into();
+            accumulator.last = value;
+            accumulator.count += 1;
+        } else {
+            *accumulator = Some(Self {
+                min: value,
+                max: value,
+                sum: value.into(),
+                last: value,
+                count: 1,
+            });
+        }
+    }
+
+    fn statistics(&self) -> Statistics<T> {
+        let count = i64::try_from(self.count).unwrap_or(i64::MAX);
+        let mean = (2 * self.sum + count).div_euclid(2 * count);
+
+        Statistics {
+            min: self.min,
+            max: self.max,
+            mean: T::try_from(mean).unwrap_or(self.last),
+            last: self.last,
+            count: self.count,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Window {
+    count: usize,
+    temperature: Option<Accumulator<i32>>,
+    humidity: Option<Accumulator<u32>>,
+    pressure: Option<Accumulator<u32>>,
+    acceleration: [Option<Accumulator<i16>>; 3],
+    battery_potential: Option<Accumulator<u16>>,
+    tx_power: Option<Accumulator<i8>>,
+    rssi: Option<Accumulator<i16>>,
+    movement_counter: Option<CounterDelta>,
+    measurement_sequence_number: Option<CounterDelta>,
+}
+
+#[derive(Clone, Debug)]
+struct TagState {
+    start: u64,
+    window: Window,
+    movement_counter: Option<u32>,
+    measurement_sequence_number: Option<u32>,
+}
+
+/// Aggregates readings of multiple tags into windows of fixed length
+///
+/// Windows are aligned to multiples of the window length since Unix epoch. Each tag has one
+/// open window at a time: a reading which belongs to a later window completes the open window,
+/// and readings which belong to an already completed window are ignored.
+#[derive(Clone, Debug)]
+pub struct Aggregator {
+    length: u64,
+    counter_ranges: CounterRanges,
+    tags: BTreeMap<Mac, TagState>,
+}
+
+impl Aggregator {
+    /// Returns an aggregator with windows of `length` seconds. Length of zero is treated as one
+    /// second.
+    #[must_use]
+    pub fn new(length: u64) -> Self {
+        Self {
+            length: length.max(1),
+            counter_ranges: CounterRanges::default(),
+            tags: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the aggregator with the given counter ranges
+    #[must_use]
+    pub fn with_counter_ranges(self, counter_ranges: CounterRanges) -> Self {
+        Self {
+            counter_ranges,
+            ..self
+        }
+    }
+
+    /// Adds a reading of a tag, and returns the previous window of the tag if the reading
+    /// belongs to a later window.
+    pub fn push<T>(&mut self, tag: Mac, reading: &Reading<T>) -> Option<Aggregate>
+    where
+        T: Acceleration
+            + BatteryPotential
+            + Humidity
+            + MeasurementSequenceNumber
+            + MovementCounter
+            + Pressure
+            + Temperature
+            + TransmitterPower,
+    {
+        let start = reading.timestamp - reading.timestamp % self.length;
+        let state = self.tags.entry(tag).or_insert_with(|| TagState {
+            start,
+            window: Window::default(),
+            movement_counter: None,
+            measurement_sequence_number: None,
+        });
+
+        if start < state.start {
+            return None;
+        }
+
+        let completed = if start > state.start {
+            let window = core::mem::take(&mut state.window);
+            let completed = Self::aggregate(tag, state.start, self.length, &window);
+            state.start = start;
+            completed
+        } else {
+            None
+        };
+
+        let values = &reading.values;
+        let window = &mut state.window;
+        window.count += 1;
+
+        Accumulator::push(
+            &mut window.temperature,
+            values.temperature_as_millicelsius(),
+        );
+        Accumulator::push(&mut window.humidity, values.humidity_as_ppm());
+        Accumulator::push(&mut window.pressure, values.pressure_as_pascals());
+        if let Some(acceleration) = values.acceleration_vector_as_milli_g() {
+            let components = [acceleration.0, acceleration.1, acceleration.2];
+            for (accumulator, component) in window.acceleration.iter_mut().zip(components) {
+                Accumulator::push(accumulator, Some(component));
+            }
+        }
+        Accumulator::push(
+            &mut window.battery_potential,
+            values.battery_potential_as_millivolts(),
+        );
+        Accumulator::push(&mut window.tx_power, values.tx_power_as_dbm());
+        Accumulator::push(&mut window.rssi, reading.rssi);
+
+        push_counter(
+            &mut window.movement_counter,
+            &mut state.movement_counter,
+            values.movement_counter(),
+            self.counter_ranges.movement_counter,
+        );
+        push_counter(
+            &mut window.measurement_sequence_number,
+            &mut state.measurement_sequence_number,
+            values.measurement_sequence_number(),
+            self.counter_ranges.measurement_sequence_number,
+        );
+
+        completed
+    }
+
+    /// Completes and returns windows which end at or before `timestamp`. Readings which belong
+    /// to completed windows are ignored after this.
+    pub fn flush_until(&mut self, timestamp: u64) -> Vec<Aggregate> {
+        let length = self.length;
+        let mut aggregates = Vec::new();
+
+        for (tag, state) in &mut self.tags {
+            if state.start + length <= timestamp {
+                let window = core::mem::take(&mut state.window);
+                aggregates.extend(Self::aggregate(*tag, state.start, length, &window));
+                state.start += length;
+            }
+        }
+
+        aggregates
+    }
+
+    /// Completes and returns all open windows
+    pub fn flush(&mut self) -> Vec<Aggregate> {
+        self.flush_until(u64::MAX)
+    }
+
+    fn aggregate(tag: Mac, start: u64, length: u64, window: &Window) -> Option<Aggregate> {
+        if window.count == 0 {
+            return None;
+        }
+
+        let [x, y, z] = &window.acceleration;
+        let acceleration = x
+            .zip(*y)
+            .zip(*z)
+            .map(|((x, y), z)| [x.statistics(), y.statistics(), z.statistics()]);
+
+        Some(Aggregate {
+            tag,
+            start,
+            end: start + length,
+            count: window.count,
+            temperature: window.temperature.as_ref().map(Accumulator::statistics),
+            humidity: window.humidity.as_ref().map(Accumulator::statistics),
+            pressure: window.pressure.as_ref().map(Accumulator::statistics),
+            acceleration,
+            battery_potential: window
+                .battery_potential
+                .as_ref()
+                .map(Accumulator::statistics),
+            tx_power: window.tx_power.as_ref().map(Accumulator::statistics),
+            rssi: window.rssi.as_ref().map(Accumulator::statistics),
+            movement_counter: window.movement_counter,
+            measurement_sequence_number: window.measurement_sequence_number,
+        })
+    }
+}
+
+fn push_counter(
+    window: &mut Option<CounterDelta>,
+    last: &mut Option<u32>,
+    value: Option<u32>,
+    range: u32,
+) {
+    let Some(value) = value else {
+        return;
+    };
+
+    let increment = last.map_or(0, |last| {
+        if value >= last {
+            value - last
+        } else {
+            (range.saturating_sub(last)).saturating_add(value)
+        }
+    });
+    let delta = window.map_or(0, |window| window.delta);
+
+    *window = Some(CounterDelta {
+        delta: delta.saturating_add(increment),
+        last: value,
+    });
+    *last = Some(value);
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::AccelerationVector;
+
+    #[derive(Default)]
+    struct Values {
+        acceleration: Option<AccelerationVector>,
+        humidity: Option<u32>,
+        measurement_sequence_number: Option<u32>,
+        movement_counter: Option<u32>,
+        temperature: Option<i32>,
+    }
+
+    impl Acceleration for Values {
+        fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
+            self.acceleration
+        }
+    }
+
+    impl BatteryPotential for Values {
+        fn battery_potential_as_millivolts(&self) -> Option<u16> {
+            None
+        }
+    }
+
+    impl Humidity for Values {
+        fn humidity_as_ppm(&self) -> Option<u32> {
+            self.humidity
+        }
+    }
+
+    impl MeasurementSequenceNumber for Values {
+        fn measurement_sequence_number(&self) -> Option<u32> {
+            self.measurement_sequence_number
+        }
+    }
+
+    impl MovementCounter for Values {
+        fn movement_counter(&self) -> Option<u32> {
+            self.movement_counter
+        }
+    }
+
+    impl Pressure for Values {
+        fn pressure_as_pascals(&self) -> Option<u32> {
+            None
+        }
+    }
+
+    impl Temperature for Values {
+        fn temperature_as_millikelvins(&self) -> Option<u32> {
+            self.temperature
+                .and_then(|value| u32::try_from(value + 273_150).ok())
+        }
+    }
+
+    impl TransmitterPower for Values {
+        fn tx_power_as_dbm(&self) -> Option<i8> {
+            None
+        }
+    }
+
+    const TAG: Mac = Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F]);
+
+    fn temperature(timestamp: u64, temperature: Option<i32>) -> Reading<Values> {
+        Reading::new(
+            timestamp,
+            Values {
+                temperature,
+                ..Values::default()
+            },
+        )
+    }
+
+    fn counters(timestamp: u64, movement_counter: u32, sequence: u32) -> Reading<Values> {
+        Reading::new(
+            timestamp,
+            Values {
+                movement_counter: Some(movement_counter),
+                measurement_sequence_number: Some(sequence),
+                ..Values::default()
+            },
+        )
+    }
+
+    #[test]
+    fn aggregator_has_default_traits() {
+        crate::testing::type_has_default_traits::<Aggregator>();
+        crate::testing::type_has_default_traits::<Aggregate>();
+    }
+
+    #[test]
+    fn statistics_ignore_unavailable_values() {
+        let mut aggregator = Aggregator::new(60);
+
+        assert_eq!(aggregator.push(TAG, &temperature(60, Some(20_000))), None);
+        assert_eq!(aggregator.push(TAG, &temperature(70, None)), None);
+        assert_eq!(aggregator.push(TAG, &temperature(80, Some(21_001))), None);
+        assert_eq!(aggregator.push(TAG, &temperature(119, Some(19_000))), None);
+
+        let aggregate = aggregator.push(TAG, &temperature(120, None)).unwrap();
+
+        assert_eq!(aggregate.start, 60);
+        assert_eq!(aggregate.end, 120);
+        assert_eq!(aggregate.count, 4);
+        assert_eq!(
+            aggregate.temperature,
+            Some(Statistics {
+                min: 19_000,
+                max: 21_001,
+                mean: 20_000,
+                last: 19_000,
+                count: 3,
+            })
+        );
+        assert_eq!(aggregate.humidity, None);
+        assert_eq!(aggregate.rssi, None);
+    }
+
+    #[test]
+    fn mean_is_rounded() {
+        let mut aggregator = Aggregator::new(60);
+        aggregator.push(TAG, &temperature(0, Some(-1)));
+        aggregator.push(TAG, &temperature(1, Some(-2)));
+
+        let aggregates = aggregator.flush();
+
+        assert_eq!(aggregates[0].temperature.unwrap().mean, -1);
+    }
+
+    #[test]
+    fn acceleration_and_rssi() {
+        let mut aggregator = Aggregator::new(60);
+        let reading = |timestamp, rssi, x| {
+            let values = Values {
+                acceleration: Some(AccelerationVector(x, 0, 1000)),
+                ..Values::default()
+            };
+            Reading::new(timestamp, values).with_rssi(rssi)
+        };
+
+        aggregator.push(TAG, &reading(0, -80, -10));
+        aggregator.push(TAG, &reading(1, -60, 30));
+
+        let aggregate = aggregator.flush().pop().unwrap();
+        let [x, _, z] = aggregate.acceleration.unwrap();
+
+        assert_eq!((x.min, x.max, x.mean, x.last), (-10, 30, 10, 30));
+        assert_eq!((z.min, z.max, z.mean, z.last), (1000, 1000, 1000, 1000));
+        assert_eq!(aggregate.rssi.map(|rssi| rssi.mean), Some(-70));
+    }
+
+    #[test]
+    fn counters_are_deltas() {
+        let mut aggregator = Aggregator::new(60);
+
+        aggregator.push(TAG, &counters(0, 250, 0xFFFA));
+        aggregator.push(TAG, &counters(10, 253, 0xFFFE));
+        aggregator.push(TAG, &counters(20, 2, 3));
+        let first = aggregator.push(TAG, &counters(60, 4, 5)).unwrap();
+        let second = aggregator.flush().pop().unwrap();
+
+        assert_eq!(
+            first.movement_counter,
+            Some(CounterDelta { delta: 7, last: 2 })
+        );
+        assert_eq!(
+            first.measurement_sequence_number,
+            Some(CounterDelta { delta: 8, last: 3 })
+        );
+        assert_eq!(
+            second.movement_counter,
+            Some(CounterDelta { delta: 2, last: 4 })
+        );
+        assert_eq!(
+            second.measurement_sequence_number,
+            Some(CounterDelta { delta: 2, last: 5 })
+        );
+    }
+
+    #[test]
+    fn custom_counter_ranges() {
+        let mut aggregator = Aggregator::new(60).with_counter_ranges(CounterRanges {
+            movement_counter: 0x100,
+            measurement_sequence_number: 0x1_0000,
+        });
+
+        aggregator.push(TAG, &counters(0, 0xFF, 0xFFFF));
+        aggregator.push(TAG, &counters(1, 0, 0));
+
+        let aggregate = aggregator.flush().pop().unwrap();
+
+        assert_eq!(aggregate.movement_counter.map(|delta| delta.delta), Some(1));
+        assert_eq!(
+            aggregate
+                .measurement_sequence_number
+                .map(|delta| delta.delta),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn late_readings_are_ignored() {
+        let mut aggregator = Aggregator::new(60);
+
+        aggregator.push(TAG, &temperature(60, Some(20_000)));
+        aggregator.push(TAG, &temperature(120, Some(21_000)));
+
+        assert_eq!(aggregator.push(TAG, &temperature(100, Some(0))), None);
+        assert_eq!(
+            aggregator.flush().pop().unwrap().temperature.unwrap().min,
+            21_000
+        );
+    }
+
+    #[test]
+    fn tags_are_aggregated_separately() {
+        let mut aggregator = Aggregator::new(3_600);
+        let other = Mac::new([0; 6]);
+
+        aggregator.push(TAG, &temperature(3_600, Some(20_000)));
+        aggregator.push(other, &temperature(3_700, Some(10_000)));
+
+        assert_eq!(aggregator.flush_until(7_199), []);
+
+        let aggregates = aggregator.flush_until(7_200);
+        let tags: Vec<_> = aggregates.iter().map(|aggregate| aggregate.tag).collect();
+        assert_eq!(tags, [other, TAG]);
+        assert_eq!(aggregates[0].temperature.unwrap().mean, 10_000);
+        assert_eq!(aggregates[1].temperature.unwrap().mean, 20_000);
+
+        assert_eq!(aggregator.push(TAG, &temperature(7_100, Some(0))), None);
+        assert_eq!(aggregator.flush(), []);
+    }
+}
@@ -0,0 +1,651 @@
+/*!
+
+This module implements threshold alerts similar to the alerts in Ruuvi Station application.
+Alerts are configured per tag with [`AlertRules`], and evaluated by [`AlertEvaluator`] from
+a stream of [`Reading`]s.
+
+An alert is triggered when a measurement goes outside of its bounds, and cleared when it returns
+inside the bounds by at least the configured hysteresis. Movement alerts are triggered when the
+movement counter of a tag changes, and offline alerts when a tag has not sent any data for
+a configured time.
+
+```rust
+use ruuvi_sensor_protocol::{
+    alerts::{AlertEvaluator, AlertEvent, AlertKind, AlertRules, AlertState, Bounds},
+    Mac, Reading, SensorValues,
+};
+# use ruuvi_sensor_protocol::ParseError;
+
+let tag = Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F]);
+let mut evaluator = AlertEvaluator::new();
+evaluator.set_rules(
+    tag,
+    AlertRules {
+        temperature: Some(Bounds::new(None, Some(20_000)).with_hysteresis(500)),
+        ..AlertRules::default()
+    },
+);
+
+let value = [
+    0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+    0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+];
+let values = SensorValues::from_manufacturer_specific_data(0x0499, value)?;
+let events = evaluator.evaluate(tag, &Reading::new(1_653_668_027, values));
+
+assert_eq!(
+    events,
+    [AlertEvent {
+        tag,
+        kind: AlertKind::Temperature,
+        state: AlertState::Triggered,
+        timestamp: 1_653_668_027,
+    }]
+);
+# Ok::<(), ParseError>(())
+```
+
+[`Reading`]: crate::Reading
+
+*/
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+use crate::{Humidity, Mac, MovementCounter, Pressure, Reading, Temperature};
+
+/// Lower and upper bounds of a measurement with hysteresis
+///
+/// A value is out of bounds when it is below `min` or above `max`. After going out of bounds,
+/// the value must be at least `hysteresis` inside the bounds before it is considered to be back
+/// in bounds.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Bounds<T> {
+    /// smallest allowed value
+    pub min: Option<T>,
+    /// largest allowed value
+    pub max: Option<T>,
+    /// distance inside the bounds required for clearing an alert
+    pub hysteresis: T,
+}
+
+impl<T: Copy + Default + Into<i64>> Bounds<T> {
+    /// Returns bounds without hysteresis
+    pub fn new(min: Option<T>, max: Option<T>) -> Self {
+        Self {
+            min,
+            max,
+            hysteresis: T::default(),
+        }
+    }
+
+    /// Returns the bounds with the given hysteresis
+    #[must_use]
+    pub fn with_hysteresis(self, hysteresis: T) -> Self {
+        Self { hysteresis, ..self }
+    }
+
+    /// Returns `Some(true)` if value is out of bounds, `Some(false)` if it is inside the bounds
+    /// by at least hysteresis, and `None` if it is between these two.
+    fn check(&self, value: T) -> Option<bool> {
+        let value = value.into();
+        let hysteresis = self.hysteresis.into();
+        let min = self.min.map(Into::into);
+        let max = self.max.map(Into::into);
+
+        if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+            Some(true)
+        } else if min.is_none_or(|min| value >= min + hysteresis)
+            && max.is_none_or(|max| value <= max - hysteresis)
+        {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Alert rules of a single tag
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AlertRules {
+    /// bounds for temperature in milli-Celsius
+    pub temperature: Option<Bounds<i32>>,
+    /// bounds for relative humidity in parts per million
+    pub humidity: Option<Bounds<u32>>,
+    /// bounds for pressure in pascals
+    pub pressure: Option<Bounds<u32>>,
+    /// bounds for received signal strength in dBm
+    pub rssi: Option<Bounds<i16>>,
+    /// trigger an alert when the movement counter changes
+    pub movement: bool,
+    /// trigger an alert when no data has been received for the given number of seconds
+    pub offline_after: Option<u64>,
+}
+
+impl AlertRules {
+    /// Returns `true` if the rules can trigger or clear alerts of the given kind
+    fn has_rule(&self, kind: AlertKind) -> bool {
+        match kind {
+            AlertKind::Temperature => self.temperature.is_some(),
+            AlertKind::Humidity => self.humidity.is_some(),
+            AlertKind::Pressure => self.pressure.is_some(),
+            AlertKind::Rssi => self.rssi.is_some(),
+            AlertKind::Movement => self.movement,
+            AlertKind::Offline => self.offline_after.is_some(),
+        }
+    }
+}
+
+/// Type of an alert
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum AlertKind {
+    /// Temperature is out of bounds
+    Temperature,
+    /// Relative humidity is out of bounds
+    Humidity,
+    /// Pressure is out of bounds
+    Pressure,
+    /// Received signal strength is out of bounds
+    Rssi,
+    /// Movement counter has changed
+    Movement,
+    /// No data has been received for a while
+    Offline,
+}
+
+/// State of an alert after an event
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AlertState {
+    /// Alert condition started
+    Triggered,
+    /// Alert condition ended
+    Cleared,
+}
+
+/// Change of an alert state
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AlertEvent {
+    /// MAC address of the tag
+    pub tag: Mac,
+    /// type of the alert
+    pub kind: AlertKind,
+    /// new state of the alert
+    pub state: AlertState,
+    /// time of the reading or the check which caused the event as seconds since Unix epoch
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TagState {
+    last_seen: Option<u64>,
+    movement_counter: Option<u32>,
+    active: BTreeSet<AlertKind>,
+}
+
+impl TagState {
+    fn update(
+        &mut self,
+        events: &mut Vec<AlertEvent>,
+        tag: Mac,
+        timestamp: u64,
+        kind: AlertKind,
+        condition: Option<bool>,
+    ) {
+        let state = match condition {
+            Some(true) if self.active.insert(kind) => AlertState::Triggered,
+            Some(false) if self.active.remove(&kind) => AlertState::Cleared,
+            _ => return,
+        };
+
+        events.push(AlertEvent {
+            tag,
+            kind,
+            state,
+            timestamp,
+        });
+    }
+}
+
+/// Evaluates alert rules of multiple tags
+///
+/// Tags are identified by their MAC address. Readings of tags without rules are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct AlertEvaluator {
+    rules: BTreeMap<Mac, AlertRules>,
+    states: BTreeMap<Mac, TagState>,
+}
+
+impl AlertEvaluator {
+    /// Returns an evaluator without any rules
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the alert rules of a tag. Alerts which are already active stay active until they
+    /// are cleared by the new rules. Active alerts without a rule in the new rules are cleared
+    /// without an event, like the alerts of [`remove_rules`](Self::remove_rules).
+    pub fn set_rules(&mut self, tag: Mac, rules: AlertRules) {
+        if let Some(state) = self.states.get_mut(&tag) {
+            state.active.retain(|kind| rules.has_rule(*kind));
+        }
+        self.rules.insert(tag, rules);
+    }
+
+    /// Removes the alert rules and the state of a tag
+    pub fn remove_rules(&mut self, tag: Mac) -> Option<AlertRules> {
+        self.states.remove(&tag);
+        self.rules.remove(&tag)
+    }
+
+    /// Returns `true` if an alert of the given kind is active for the tag
+    #[must_use]
+    pub fn is_active(&self, tag: Mac, kind: AlertKind) -> bool {
+        self.states
+            .get(&tag)
+            .is_some_and(|state| state.active.contains(&kind))
+    }
+
+    /// Evaluates the alert rules of a tag against a new reading, and returns alert events
+    /// caused by the reading. Unavailable measurements do not change the state of their alerts.
+    pub fn evaluate<T>(&mut self, tag: Mac, reading: &Reading<T>) -> Vec<AlertEvent>
+    where
+        T: Humidity + MovementCounter + Pressure + Temperature,
+    {
+        let mut events = Vec::new();
+        let Some(rules) = self.rules.get(&tag) else {
+            return events;
+        };
+
+        let state = self.states.entry(tag).or_default();
+        let values = &reading.values;
+        let mut update = |kind, condition| {
+            state.update(&mut events, tag, reading.timestamp, kind, condition);
+        };
+
+        let temperature = rules.temperature.zip(values.temperature_as_millicelsius());
+        update(
+            AlertKind::Temperature,
+            temperature.and_then(|(bounds, value)| bounds.check(value)),
+        );
+
+        let humidity = rules.humidity.zip(values.humidity_as_ppm());
+        update(
+            AlertKind::Humidity,
+            humidity.and_then(|(bounds, value)| bounds.check(value)),
+        );
+
+        let pressure = rules.pressure.zip(values.pressure_as_pascals());
+        update(
+            AlertKind::Pressure,
+            pressure.and_then(|(bounds, value)| bounds.check(value)),
+        );
+
+        let rssi = rules.rssi.zip(reading.rssi);
+        update(
+            AlertKind::Rssi,
+            rssi.and_then(|(bounds, value)| bounds.check(value)),
+        );
+
+        let movement_counter = values.movement_counter();
+        if rules.movement {
+            let moved = state
+                .movement_counter
+                .zip(movement_counter)
+                .map(|(previous, current)| previous != current);
+            state.update(
+                &mut events,
+                tag,
+                reading.timestamp,
+                AlertKind::Movement,
+                moved,
+            );
+        }
+        state.movement_counter = movement_counter.or(state.movement_counter);
+
+        state.update(
+            &mut events,
+            tag,
+            reading.timestamp,
+            AlertKind::Offline,
+            Some(false),
+        );
+        state.last_seen = state.last_seen.max(Some(reading.timestamp));
+
+        events
+    }
+
+    /// Checks which tags have not sent any data within their offline limit at time `now`, and
+    /// returns alert events for tags that went offline. Tags which have not sent any data after
+    /// their rules were set are not considered offline.
+    pub fn check_offline(&mut self, now: u64) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+
+        for (tag, state) in &mut self.states {
+            let limit = self.rules.get(tag).and_then(|rules| rules.offline_after);
+            let offline = limit
+                .zip(state.last_seen)
+                .map(|(limit, last_seen)| now.saturating_sub(last_seen) > limit);
+
+            if offline == Some(true) {
+                state.update(&mut events, *tag, now, AlertKind::Offline, offline);
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Values {
+        humidity: Option<u32>,
+        movement_counter: Option<u32>,
+        pressure: Option<u32>,
+        temperature: Option<i32>,
+    }
+
+    impl Humidity for Values {
+        fn humidity_as_ppm(&self) -> Option<u32> {
+            self.humidity
+        }
+    }
+
+    impl MovementCounter for Values {
+        fn movement_counter(&self) -> Option<u32> {
+            self.movement_counter
+        }
+    }
+
+    impl Pressure for Values {
+        fn pressure_as_pascals(&self) -> Option<u32> {
+            self.pressure
+        }
+    }
+
+    impl Temperature for Values {
+        fn temperature_as_millikelvins(&self) -> Option<u32> {
+            self.temperature
+                .and_then(|value| u32::try_from(value + 273_150).ok())
+        }
+    }
+
+    const TAG: Mac = Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F]);
+
+    fn event(kind: AlertKind, state: AlertState, timestamp: u64) -> AlertEvent {
+        AlertEvent {
+            tag: TAG,
+            kind,
+            state,
+            timestamp,
+        }
+    }
+
+    fn temperature(timestamp: u64, temperature: i32) -> Reading<Values> {
+        Reading::new(
+            timestamp,
+            Values {
+                temperature: Some(temperature),
+                ..Values::default()
+            },
+        )
+    }
+
+    #[test]
+    fn alert_types_have_default_traits() {
+        crate::testing::type_has_default_traits::<AlertEvaluator>();
+        crate::testing::type_has_default_traits::<AlertEvent>();
+    }
+
+    macro_rules! test_bounds {
+        (
+            $(
+                test $name: ident {
+                    bounds: $bounds: expr,
+                    values: [$($value: expr => $result: expr),+ $(,)?],
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let bounds = $bounds;
+                    $(
+                        assert_eq!(bounds.check($value), $result, "value {}", $value);
+                    )+
+                }
+            )+
+        };
+    }
+
+    test_bounds! {
+        test min_and_max {
+            bounds: Bounds::new(Some(-10), Some(10)),
+            values: [-11 => Some(true), -10 => Some(false), 10 => Some(false), 11 => Some(true)],
+        }
+
+        test only_max {
+            bounds: Bounds::new(None, Some(10_u32)),
+            values: [0 => Some(false), 11 => Some(true)],
+        }
+
+        test hysteresis {
+            bounds: Bounds::new(Some(-10), Some(10)).with_hysteresis(2),
+            values: [
+                -11 => Some(true),
+                -9 => None,
+                -8 => Some(false),
+                8 => Some(false),
+                9 => None,
+                11 => Some(true),
+            ],
+        }
+    }
+
+    #[test]
+    fn temperature_alert_with_hysteresis() {
+        let mut evaluator = AlertEvaluator::new();
+        evaluator.set_rules(
+            TAG,
+            AlertRules {
+                temperature: Some(Bounds::new(None, Some(25_000)).with_hysteresis(1_000)),
+                ..AlertRules::default()
+            },
+        );
+
+        assert_eq!(evaluator.evaluate(TAG, &temperature(1, 24_000)), []);
+        assert_eq!(
+            evaluator.evaluate(TAG, &temperature(2, 25_500)),
+            [event(AlertKind::Temperature, AlertState::Triggered, 2)]
+        );
+        assert_eq!(evaluator.evaluate(TAG, &temperature(3, 26_000)), []);
+        assert_eq!(evaluator.evaluate(TAG, &temperature(4, 24_500)), []);
+        assert!(evaluator.is_active(TAG, AlertKind::Temperature));
+        assert_eq!(
+            evaluator.evaluate(TAG, &temperature(5, 24_000)),
+            [event(AlertKind::Temperature, AlertState::Cleared, 5)]
+        );
+        assert!(!evaluator.is_active(TAG, AlertKind::Temperature));
+    }
+
+    #[test]
+    fn unavailable_measurement_keeps_state() {
+        let mut evaluator = AlertEvaluator::new();
+        evaluator.set_rules(
+            TAG,
+            AlertRules {
+                humidity: Some(Bounds::new(Some(200_000), None)),
+                pressure: Some(Bounds::new(Some(90_000), Some(110_000))),
+                ..AlertRules::default()
+            },
+        );
+
+        let dry = Values {
+            humidity: Some(100_000),
+            pressure: Some(100_000),
+            ..Values::default()
+        };
+        assert_eq!(
+            evaluator.evaluate(TAG, &Reading::new(1, dry)),
+            [event(AlertKind::Humidity, AlertState::Triggered, 1)]
+        );
+        assert_eq!(
+            evaluator.evaluate(TAG, &Reading::new(2, Values::default())),
+            []
+        );
+        assert!(evaluator.is_active(TAG, AlertKind::Humidity));
+    }
+
+    #[test]
+    fn rssi_alert() {
+        let mut evaluator = AlertEvaluator::new();
+        evaluator.set_rules(
+            TAG,
+            AlertRules {
+                rssi: Some(Bounds::new(Some(-80), None).with_hysteresis(5)),
+                ..AlertRules::default()
+            },
+        );
+
+        let weak = Reading::new(1, Values::default()).with_rssi(-90);
+        let strong = Reading::new(2, Values::default()).with_rssi(-70);
+
+        assert_eq!(
+            evaluator.evaluate(TAG, &weak),
+            [event(AlertKind::Rssi, AlertState::Triggered, 1)]
+        );
+        assert_eq!(
+            evaluator.evaluate(TAG, &strong),
+            [event(AlertKind::Rssi, AlertState::Cleared, 2)]
+        );
+    }
+
+    #[test]
+    fn movement_alert() {
+        let mut evaluator = AlertEvaluator::new();
+        evaluator.set_rules(
+            TAG,
+            AlertRules {
+                movement: true,
+                ..AlertRules::default()
+            },
+        );
+
+        let movement = |timestamp, movement_counter| {
+            Reading::new(
+                timestamp,
+                Values {
+                    movement_counter,
+                    ..Values::default()
+                },
+            )
+        };
+
+        assert_eq!(evaluator.evaluate(TAG, &movement(1, Some(10))), []);
+        assert_eq!(evaluator.evaluate(TAG, &movement(2, None)), []);
+        assert_eq!(
+            evaluator.evaluate(TAG, &movement(3, Some(11))),
+            [event(AlertKind::Movement, AlertState::Triggered, 3)]
+        );
+        assert_eq!(
+            evaluator.evaluate(TAG, &movement(4, Some(11))),
+            [event(AlertKind::Movement, AlertState::Cleared, 4)]
+        );
+    }
+
+    #[test]
+    fn offline_alert() {
+        let mut evaluator = AlertEvaluator::new();
+        evaluator.set_rules(
+            TAG,
+            AlertRules {
+                offline_after: Some(60),
+                ..AlertRules::default()
+            },
+        );
+
+        assert_eq!(evaluator.check_offline(1_000), []);
+        assert_eq!(evaluator.evaluate(TAG, &temperature(1_000, 20_000)), []);
+        assert_eq!(evaluator.check_offline(1_060), []);
+        assert_eq!(
+            evaluator.check_offline(1_061),
+            [event(AlertKind::Offline, AlertState::Triggered, 1_061)]
+        );
+        assert_eq!(evaluator.check_offline(1_100), []);
+        assert_eq!(
+            evaluator.evaluate(TAG, &temperature(1_101, 20_000)),
+            [event(AlertKind::Offline, AlertState::Cleared, 1_101)]
+        );
+    }
+
+    #[test]
+    fn removed_rule_clears_alert() {
+        let mut evaluator = AlertEvaluator::new();
+        let rules = AlertRules {
+            temperature: Some(Bounds::new(None, Some(25_000))),
+            humidity: Some(Bounds::new(Some(200_000), None)),
+            ..AlertRules::default()
+        };
+        evaluator.set_rules(TAG, rules);
+
+        let reading = Reading::new(
+            1,
+            Values {
+                humidity: Some(100_000),
+                temperature: Some(26_000),
+                ..Values::default()
+            },
+        );
+        assert_eq!(
+            evaluator.evaluate(TAG, &reading),
+            [
+                event(AlertKind::Temperature, AlertState::Triggered, 1),
+                event(AlertKind::Humidity, AlertState::Triggered, 1),
+            ]
+        );
+
+        evaluator.set_rules(
+            TAG,
+            AlertRules {
+                temperature: None,
+                ..rules
+            },
+        );
+        assert!(!evaluator.is_active(TAG, AlertKind::Temperature));
+        assert!(evaluator.is_active(TAG, AlertKind::Humidity));
+
+        evaluator.set_rules(TAG, rules);
+        assert_eq!(
+            evaluator.evaluate(TAG, &temperature(2, 26_000)),
+            [event(AlertKind::Temperature, AlertState::Triggered, 2)]
+        );
+    }
+
+    #[test]
+    fn tag_without_rules() {
+        let mut evaluator = AlertEvaluator::new();
+        evaluator.set_rules(
+            TAG,
+            AlertRules {
+                temperature: Some(Bounds::new(None, Some(0))),
+                ..AlertRules::default()
+            },
+        );
+
+        assert_eq!(
+            evaluator.evaluate(Mac::new([0; 6]), &temperature(1, 20_000)),
+            []
+        );
+        assert_eq!(
+            evaluator.remove_rules(TAG).map(|rules| rules.movement),
+            Some(false)
+        );
+        assert_eq!(evaluator.evaluate(TAG, &temperature(1, 20_000)), []);
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+
+Bridge which subscribes to MQTT messages published by Ruuvi Gateway, decodes them, and republishes
+the measurements as JSON objects, `InfluxDB` lines or one topic per measurement.
+
+*/
+
+use core::time::Duration;
+use std::{env, process::ExitCode};
+
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, Publish, QoS};
+use ruuvi_sensor_protocol::gateway::{parse_mqtt_message, MqttError};
+use tokio::sync::mpsc;
+
+use crate::output::{encode, Format};
+
+mod output;
+
+const USAGE: &str = "\
+Usage: ruuvi-mqtt-bridge [OPTIONS]
+
+Options:
+  --host <HOST>              MQTT broker host [default: localhost]
+  --port <PORT>              MQTT broker port [default: 1883]
+  --client-id <ID>           MQTT client id [default: ruuvi-mqtt-bridge]
+  --username <USERNAME>      MQTT username
+  --password <PASSWORD>      MQTT password
+  --subscribe <FILTER>       Topic filter of gateway messages [default: ruuvi/#]
+  --output-prefix <PREFIX>   Topic prefix of republished messages [default: ruuvi-bridge]
+  --format <FORMAT>          Format of republished messages: json, influx or fields [default: json]
+  --help                     Print this help";
+
+/// Delay before polling the connection again after an error, which reconnects to the broker
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+struct Options {
+    host: String,
+    port: u16,
+    client_id: String,
+    credentials: Option<(String, String)>,
+    subscribe: String,
+    output_prefix: String,
+    format: Format,
+}
+
+impl Options {
+    /// Parses command line arguments, returning `None` if help was requested
+    fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Options {
+            host: "localhost".into(),
+            port: 1883,
+            client_id: "ruuvi-mqtt-bridge".into(),
+            credentials: None,
+            subscribe: "ruuvi/#".into(),
+            output_prefix: "ruuvi-bridge".into(),
+            format: Format::Json,
+        };
+        let mut username = None;
+        let mut password = None;
+
+        while let Some(argument) = arguments.next() {
+            if argument == "--help" {
+                return Ok(None);
+            }
+
+            let value = arguments
+                .next()
+                .ok_or_else(|| format!("Missing value for {argument}"))?;
+
+            match argument.as_str() {
+                "--host" => options.host = value,
+                "--port" => {
+                    options.port = value
+                        .parse()
+                        .map_err(|_| format!("Invalid port {value:?}"))?;
+                }
+                "--client-id" => options.client_id = value,
+                "--username" => username = Some(value),
+                "--password" => password = Some(value),
+                "--subscribe" => options.subscribe = value,
+                "--output-prefix" => options.output_prefix = value,
+                "--format" => options.format = value.parse()?,
+                _ => return Err(format!("Unknown option {argument}")),
+            }
+        }
+
+        options.credentials = match (username, password) {
+            (Some(username), password) => Some((username, password.unwrap_or_default())),
+            (None, Some(_)) => return Err("Password requires a username".into()),
+            (None, None) => None,
+        };
+
+        Ok(Some(options))
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => run(&options).await,
+        Ok(None) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(options: &Options) -> ExitCode {
+    let mut mqtt_options = MqttOptions::new(&options.client_id, &options.host, options.port);
+    mqtt_options.set_keep_alive(Duration::from_secs(30));
+    if let Some((username, password)) = &options.credentials {
+        mqtt_options.set_credentials(username, password);
+    }
+
+    let (client, event_loop) = AsyncClient::new(mqtt_options, 100);
+    let (packets, mut received) = mpsc::unbounded_channel();
+    tokio::spawn(poll(event_loop, packets));
+
+    while let Some(packet) = received.recv().await {
+        match packet {
+            Packet::ConnAck(_) => {
+                if let Err(error) = client.subscribe(&options.subscribe, QoS::AtMostOnce).await {
+                    eprintln!("Failed to subscribe to {}: {error}", options.subscribe);
+                    return ExitCode::FAILURE;
+                }
+            }
+            Packet::Publish(publish) => bridge(&client, options, &publish).await,
+            _ => {}
+        }
+    }
+
+    ExitCode::FAILURE
+}
+
+/// Drives the connection and forwards connection acknowledgements and incoming messages.
+///
+/// The connection is polled on its own task, so that outgoing messages are sent while the
+/// messages are bridged. The channel is unbounded, as blocking here would stop sending the
+/// outgoing messages which the bridging waits for.
+async fn poll(mut event_loop: EventLoop, packets: mpsc::UnboundedSender<Packet>) {
+    loop {
+        let packet = match event_loop.poll().await {
+            Ok(Event::Incoming(packet @ (Packet::ConnAck(_) | Packet::Publish(_)))) => packet,
+            Ok(_) => continue,
+            Err(error) => {
+                eprintln!("Connection error: {error}");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        if packets.send(packet).is_err() {
+            return;
+        }
+    }
+}
+
+/// Decodes and republishes a message. Messages to other than tag topics are ignored, and messages
+/// which fail to parse are reported without stopping the bridge. Publishing waits for room in the
+/// outgoing queue, so bursts of messages are not dropped.
+async fn bridge(client: &AsyncClient, options: &Options, publish: &Publish) {
+    let own_topic = publish
+        .topic
+        .strip_prefix(&options.output_prefix)
+        .is_some_and(|rest| rest.starts_with('/'));
+    if own_topic {
+        return;
+    }
+
+    match parse_mqtt_message(&publish.topic, &publish.payload) {
+        Ok(message) => {
+            for (topic, payload) in encode(&message, options.format, &options.output_prefix) {
+                if let Err(error) = client
+                    .publish(&topic, QoS::AtMostOnce, false, payload)
+                    .await
+                {
+                    eprintln!("Failed to publish to {topic}: {error}");
+                }
+            }
+        }
+        Err(MqttError::Topic(_)) => {}
+        Err(error) => eprintln!("Skipping message to {}: {error}", publish.topic),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &[&str]) -> Result<Option<Options>, String> {
+        Options::parse(arguments.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn default_options() {
+        let options = parse(&[]).ok().flatten();
+
+        assert!(matches!(
+            options,
+            Some(Options {
+                port: 1883,
+                credentials: None,
+                format: Format::Json,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn custom_options() {
+        let options = parse(&[
+            "--host",
+            "broker",
+            "--port",
+            "8883",
+            "--username",
+            "user",
+            "--format",
+            "influx",
+        ])
+        .ok()
+        .flatten();
+
+        assert!(matches!(
+            options,
+            Some(Options {
+                host,
+                port: 8883,
+                credentials: Some((username, password)),
+                format: Format::Influx,
+                ..
+            }) if host == "broker" && username == "user" && password.is_empty()
+        ));
+    }
+
+    #[test]
+    fn help() {
+        assert!(matches!(parse(&["--port", "1", "--help"]), Ok(None)));
+    }
+
+    #[test]
+    fn invalid_options() {
+        let cases = [
+            (&["--port", "broker"][..], "Invalid port \"broker\""),
+            (
+                &["--format", "csv"],
+                "Unknown format \"csv\", expected json, influx or fields",
+            ),
+            (&["--password", "secret"], "Password requires a username"),
+            (&["--host"], "Missing value for --host"),
+            (&["--verbose", "true"], "Unknown option --verbose"),
+        ];
+
+        for (arguments, error) in cases {
+            assert_eq!(parse(arguments).err().as_deref(), Some(error));
+        }
+    }
+}
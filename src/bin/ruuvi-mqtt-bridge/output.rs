@@ -0,0 +1,264 @@
+use core::{fmt::Write, str::FromStr};
+
+use ruuvi_sensor_protocol::{
+    gateway::MqttMessage, Acceleration, AccelerationVector, BatteryPotential, DataFormat, Humidity,
+    MeasurementSequenceNumber, MovementCounter, Pressure, SensorValues, Temperature,
+    TransmitterPower,
+};
+use serde_json::{Map, Number as JsonNumber, Value};
+
+/// Format of the republished messages
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// One JSON object per message, published to `<prefix>/<tag MAC>`
+    Json,
+    /// One `InfluxDB` line per message, published to `<prefix>/<tag MAC>`
+    Influx,
+    /// One plain number per measurement, published to `<prefix>/<tag MAC>/<measurement>`
+    Fields,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "json" => Ok(Format::Json),
+            "influx" => Ok(Format::Influx),
+            "fields" => Ok(Format::Fields),
+            _ => Err(format!(
+                "Unknown format {value:?}, expected json, influx or fields"
+            )),
+        }
+    }
+}
+
+/// Measurement in the unit used by Ruuvi Gateway for decoded measurements
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Number {
+    Integer(i64),
+    Decimal(f64),
+}
+
+impl Number {
+    fn scaled(value: impl Into<f64>, divisor: f64) -> Self {
+        Number::Decimal(value.into() / divisor)
+    }
+
+    fn to_json(self) -> Option<Value> {
+        match self {
+            Number::Integer(value) => Some(Value::from(value)),
+            Number::Decimal(value) => JsonNumber::from_f64(value).map(Value::Number),
+        }
+    }
+}
+
+/// Returns the measurements of the values with the names and units of the decoded measurements
+/// published by Ruuvi Gateway: temperature in Celsius, humidity in percent, pressure in pascals,
+/// acceleration in G and voltage in volts.
+fn measurements(data_format: Option<u8>, values: &SensorValues) -> Vec<(&'static str, Number)> {
+    let mut measurements = Vec::new();
+    let mut push = |name, number: Option<Number>| {
+        if let Some(number) = number {
+            measurements.push((name, number));
+        }
+    };
+
+    let data_format = values
+        .data_format()
+        .map(DataFormat::version)
+        .or(data_format);
+    let acceleration = values.acceleration_vector_as_milli_g();
+    let component = |component: fn(AccelerationVector) -> i16| {
+        acceleration.map(|vector| Number::scaled(component(vector), 1000.0))
+    };
+
+    push(
+        "dataFormat",
+        data_format.map(|format| Number::Integer(format.into())),
+    );
+    push(
+        "temperature",
+        values
+            .temperature_as_millicelsius()
+            .map(|value| Number::scaled(value, 1000.0)),
+    );
+    push(
+        "humidity",
+        values
+            .humidity_as_ppm()
+            .map(|value| Number::scaled(value, 10_000.0)),
+    );
+    push(
+        "pressure",
+        values
+            .pressure_as_pascals()
+            .map(|value| Number::Integer(value.into())),
+    );
+    push("accelX", component(|AccelerationVector(x, _, _)| x));
+    push("accelY", component(|AccelerationVector(_, y, _)| y));
+    push("accelZ", component(|AccelerationVector(_, _, z)| z));
+    push(
+        "voltage",
+        values
+            .battery_potential_as_millivolts()
+            .map(|value| Number::scaled(value, 1000.0)),
+    );
+    push(
+        "txPower",
+        values
+            .tx_power_as_dbm()
+            .map(|value| Number::Integer(value.into())),
+    );
+    push(
+        "movementCounter",
+        values
+            .movement_counter()
+            .map(|value| Number::Integer(value.into())),
+    );
+    push(
+        "measurementSequenceNumber",
+        values
+            .measurement_sequence_number()
+            .map(|value| Number::Integer(value.into())),
+    );
+
+    measurements
+}
+
+/// Encodes a message in the given format, returning topics and payloads of the messages to
+/// publish
+pub fn encode(message: &MqttMessage<'_>, format: Format, prefix: &str) -> Vec<(String, String)> {
+    let tag = message.tag();
+    let gateway = message.topic.gateway;
+    let measurements = measurements(message.payload.data_format, &message.payload.data);
+    let topic = format!("{prefix}/{tag}");
+
+    match format {
+        Format::Json => {
+            let mut object = Map::new();
+            object.insert("mac".into(), tag.to_string().into());
+            if let Some(gateway) = gateway {
+                object.insert("gw_mac".into(), gateway.to_string().into());
+            }
+            for (name, number) in measurements {
+                if let Some(value) = number.to_json() {
+                    object.insert(name.into(), value);
+                }
+            }
+
+            vec![(topic, Value::Object(object).to_string())]
+        }
+        Format::Influx => {
+            if measurements.is_empty() {
+                return Vec::new();
+            }
+
+            let mut line = format!("ruuvi,mac={tag}");
+            if let Some(gateway) = gateway {
+                let _ = write!(line, ",gw_mac={gateway}");
+            }
+            for (index, (name, number)) in measurements.into_iter().enumerate() {
+                let separator = if index == 0 { ' ' } else { ',' };
+                let _ = match number {
+                    Number::Integer(value) => write!(line, "{separator}{name}={value}i"),
+                    Number::Decimal(value) => write!(line, "{separator}{name}={value}"),
+                };
+            }
+
+            vec![(topic, line)]
+        }
+        Format::Fields => measurements
+            .into_iter()
+            .map(|(name, number)| {
+                let payload = match number {
+                    Number::Integer(value) => value.to_string(),
+                    Number::Decimal(value) => value.to_string(),
+                };
+                (format!("{topic}/{name}"), payload)
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruuvi_sensor_protocol::gateway::parse_mqtt_message;
+
+    use super::*;
+
+    const TOPIC: &str = "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6";
+    const RAW_PAYLOAD: &str = "{\"data\": \"02010611FF990403170145355803E804E705E60886\"}";
+    const DECODED_PAYLOAD: &str = "{\"temperature\": 24.3, \"humidity\": 53.49}";
+
+    macro_rules! test_encode {
+        (
+            $(
+                test $name: ident {
+                    input: ($topic: expr, $payload: expr, $format: expr),
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let message = parse_mqtt_message($topic, $payload).ok();
+                    let encoded = message.map(|message| encode(&message, $format, "bridge"));
+                    let expected: Vec<(&str, &str)> = $result;
+                    let expected = expected
+                        .into_iter()
+                        .map(|(topic, payload)| (topic.to_string(), payload.to_string()))
+                        .collect();
+                    assert_eq!(encoded, Some(expected));
+                }
+            )+
+        };
+    }
+
+    test_encode! {
+        test json {
+            input: (TOPIC, RAW_PAYLOAD, Format::Json),
+            result: vec![(
+                "bridge/F4:1F:0C:28:CB:D6",
+                "{\"accelX\":1.0,\"accelY\":1.255,\"accelZ\":1.51,\"dataFormat\":3,\
+                 \"gw_mac\":\"C8:25:2D:8E:9C:2C\",\"humidity\":11.5,\"mac\":\"F4:1F:0C:28:CB:D6\",\
+                 \"pressure\":63656,\"temperature\":1.69,\"voltage\":2.182}",
+            )],
+        }
+
+        test json_without_gateway {
+            input: ("ruuvi/F4:1F:0C:28:CB:D6", DECODED_PAYLOAD, Format::Json),
+            result: vec![(
+                "bridge/F4:1F:0C:28:CB:D6",
+                "{\"humidity\":53.49,\"mac\":\"F4:1F:0C:28:CB:D6\",\"temperature\":24.3}",
+            )],
+        }
+
+        test influx {
+            input: (TOPIC, RAW_PAYLOAD, Format::Influx),
+            result: vec![(
+                "bridge/F4:1F:0C:28:CB:D6",
+                "ruuvi,mac=F4:1F:0C:28:CB:D6,gw_mac=C8:25:2D:8E:9C:2C dataFormat=3i,\
+                 temperature=1.69,humidity=11.5,pressure=63656i,accelX=1,accelY=1.255,\
+                 accelZ=1.51,voltage=2.182",
+            )],
+        }
+
+        test fields {
+            input: ("ruuvi/F4:1F:0C:28:CB:D6", DECODED_PAYLOAD, Format::Fields),
+            result: vec![
+                ("bridge/F4:1F:0C:28:CB:D6/temperature", "24.3"),
+                ("bridge/F4:1F:0C:28:CB:D6/humidity", "53.49"),
+            ],
+        }
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("influx".parse(), Ok(Format::Influx));
+        assert_eq!("fields".parse(), Ok(Format::Fields));
+        assert_eq!("csv".parse::<Format>().ok(), None);
+    }
+}
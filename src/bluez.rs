@@ -0,0 +1,406 @@
+/*!
+
+Decoding of RuuviTag advertisements from the properties of BlueZ devices.
+
+BlueZ reports the advertisements it receives in the properties of `org.bluez.Device1` objects on
+D-Bus. `ManufacturerData` property is a dictionary of manufacturer ids and data (`a{qv}`), and
+`ServiceData` property a dictionary of service UUIDs and data (`a{sv}`), where each data is an
+array of bytes wrapped in a variant. This module unwraps these D-Bus values and decodes the data
+into a [`ScanResult`], in the same way as advertisements received from other scanners.
+
+[`decode_device_properties`] takes all properties of a device, as returned by
+`org.freedesktop.DBus.Properties.GetAll` or `org.freedesktop.DBus.ObjectManager` with [zbus][1],
+and [`decode_device_data`] takes the dictionaries already converted to plain maps.
+
+[1]: https://docs.rs/zbus
+[`ScanResult`]: crate::ScanResult
+
+```rust
+use std::collections::HashMap;
+
+use ruuvi_sensor_protocol::{bluez::decode_device_data, Mac, Temperature};
+# use ruuvi_sensor_protocol::DecodeError;
+
+let manufacturer_data = HashMap::from([(
+    0x0499,
+    vec![0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86],
+)]);
+let address = Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]);
+
+let result = decode_device_data(address, Some(-62), &manufacturer_data, &HashMap::new())?;
+
+assert_eq!(result.address, address);
+assert_eq!(result.rssi, Some(-62));
+assert_eq!(result.values.temperature_as_millicelsius(), Some(1690));
+# Ok::<(), DecodeError>(())
+```
+*/
+
+use core::{
+    fmt::{self, Display},
+    hash::BuildHasher,
+};
+use std::collections::HashMap;
+
+use zvariant::{OwnedValue, Value};
+
+use crate::{
+    decode::{DecodeError, ScanResult, MANUFACTURER_DATA_ID},
+    Mac, ParseMacError,
+};
+
+/// UUID of Eddystone service as formatted by BlueZ
+const EDDYSTONE_SERVICE: &str = "0000feaa-0000-1000-8000-00805f9b34fb";
+
+/// Errors which can occur when decoding the properties of a BlueZ device
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum PropertiesError {
+    /// Property is missing or its value does not have the type used by BlueZ
+    InvalidProperty(&'static str),
+    /// `Address` property is not a valid Bluetooth address
+    Address(ParseMacError),
+    /// Advertisement data could not be decoded
+    Decode(DecodeError),
+}
+
+impl Display for PropertiesError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            PropertiesError::InvalidProperty(name) => {
+                write!(formatter, "Missing or invalid device property {name}")
+            }
+            PropertiesError::Address(error) => write!(formatter, "Invalid device address: {error}"),
+            PropertiesError::Decode(error) => write!(formatter, "{error}"),
+        }
+    }
+}
+
+impl core::error::Error for PropertiesError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            PropertiesError::InvalidProperty(_) => None,
+            PropertiesError::Address(error) => Some(error),
+            PropertiesError::Decode(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseMacError> for PropertiesError {
+    fn from(error: ParseMacError) -> Self {
+        PropertiesError::Address(error)
+    }
+}
+
+impl From<DecodeError> for PropertiesError {
+    fn from(error: DecodeError) -> Self {
+        PropertiesError::Decode(error)
+    }
+}
+
+/// Decodes the Ruuvi data in the `ManufacturerData` and `ServiceData` properties of a BlueZ
+/// device, converted to maps of manufacturer ids and service UUIDs to the data.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::NoRuuviData`] if the maps contain neither Ruuvi manufacturer specific
+/// data nor an Eddystone URL of a tag, and [`DecodeError::Parse`] if the data is not valid.
+pub fn decode_device_data<M: BuildHasher, S: BuildHasher>(
+    address: Mac,
+    rssi: Option<i16>,
+    manufacturer_data: &HashMap<u16, Vec<u8>, M>,
+    service_data: &HashMap<String, Vec<u8>, S>,
+) -> Result<ScanResult, DecodeError> {
+    ScanResult::decode(
+        address,
+        rssi,
+        manufacturer_data
+            .get(&MANUFACTURER_DATA_ID)
+            .map(Vec::as_slice),
+        service_data
+            .iter()
+            .find(|(uuid, _)| uuid.eq_ignore_ascii_case(EDDYSTONE_SERVICE))
+            .map(|(_, data)| data.as_slice()),
+    )
+}
+
+/// Decodes the Ruuvi data in the properties of a BlueZ `org.bluez.Device1` object.
+///
+/// `Address` property is required, and `RSSI`, `ManufacturerData` and `ServiceData` properties
+/// are used when present.
+///
+/// # Errors
+///
+/// Returns [`PropertiesError::InvalidProperty`] if the address is missing or a property does not
+/// have the type used by BlueZ, [`PropertiesError::Address`] if the address is not valid, and
+/// [`PropertiesError::Decode`] if the properties do not contain valid Ruuvi data.
+pub fn decode_device_properties<S: BuildHasher>(
+    properties: &HashMap<String, OwnedValue, S>,
+) -> Result<ScanResult, PropertiesError> {
+    let address = match properties.get("Address").map(|value| inner(value)) {
+        Some(Value::Str(address)) => address.as_str().parse()?,
+        _ => return Err(PropertiesError::InvalidProperty("Address")),
+    };
+    let rssi = match properties.get("RSSI").map(|value| inner(value)) {
+        None => None,
+        Some(Value::I16(rssi)) => Some(*rssi),
+        Some(_) => return Err(PropertiesError::InvalidProperty("RSSI")),
+    };
+    let manufacturer_data = dictionary(properties, "ManufacturerData", |key| match key {
+        Value::U16(id) => Some(*id),
+        _ => None,
+    })?;
+    let service_data = dictionary(properties, "ServiceData", |key| match key {
+        Value::Str(uuid) => Some(String::from(uuid.as_str())),
+        _ => None,
+    })?;
+
+    Ok(decode_device_data(
+        address,
+        rssi,
+        &manufacturer_data,
+        &service_data,
+    )?)
+}
+
+/// Returns the value inside variants
+fn inner<'a>(mut value: &'a Value<'a>) -> &'a Value<'a> {
+    while let Value::Value(variant) = value {
+        value = variant;
+    }
+    value
+}
+
+/// Converts a dictionary property with byte array values to a map, returning an empty map if the
+/// property is missing
+fn dictionary<S: BuildHasher, K: Eq + core::hash::Hash>(
+    properties: &HashMap<String, OwnedValue, S>,
+    name: &'static str,
+    key: impl Fn(&Value<'_>) -> Option<K>,
+) -> Result<HashMap<K, Vec<u8>>, PropertiesError> {
+    let Some(value) = properties.get(name) else {
+        return Ok(HashMap::new());
+    };
+    let Value::Dict(dictionary) = inner(value) else {
+        return Err(PropertiesError::InvalidProperty(name));
+    };
+
+    dictionary
+        .iter()
+        .map(|(entry_key, entry_value)| {
+            let Value::Array(array) = inner(entry_value) else {
+                return None;
+            };
+            let data = array
+                .iter()
+                .map(|byte| match byte {
+                    Value::U8(byte) => Some(*byte),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?;
+
+            Some((key(inner(entry_key))?, data))
+        })
+        .collect::<Option<_>>()
+        .ok_or(PropertiesError::InvalidProperty(name))
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use zvariant::{Dict, Signature};
+
+    use super::*;
+    use crate::Transport;
+
+    const ADDRESS: &str = "F4:1F:0C:28:CB:D6";
+
+    const V5: [u8; 24] = [
+        0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+        0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    ];
+
+    const EDDYSTONE_V4: &[u8] = b"\x10\xF9\x03ruu.vi/#BEAYAMFsA";
+
+    #[test]
+    fn properties_error_has_default_traits() {
+        crate::testing::type_has_default_traits::<PropertiesError>();
+    }
+
+    #[test]
+    fn eddystone_service_uuid_is_case_insensitive() {
+        let service_data = HashMap::from([(
+            String::from("0000FEAA-0000-1000-8000-00805F9B34FB"),
+            EDDYSTONE_V4.to_vec(),
+        )]);
+        let result = decode_device_data(
+            ADDRESS.parse().unwrap(),
+            None,
+            &HashMap::new(),
+            &service_data,
+        );
+
+        assert!(matches!(
+            result,
+            Ok(ScanResult {
+                transport: Transport::EddystoneUrl,
+                ..
+            })
+        ));
+    }
+
+    fn variant(value: Value<'static>) -> Value<'static> {
+        Value::Value(Box::new(value))
+    }
+
+    fn bytes(data: &[u8]) -> Value<'static> {
+        variant(Value::from(data.to_vec()))
+    }
+
+    fn properties(entries: Vec<(&str, Value<'static>)>) -> HashMap<String, OwnedValue> {
+        entries
+            .into_iter()
+            .map(|(name, value)| (name.into(), OwnedValue::try_from(value).unwrap()))
+            .collect()
+    }
+
+    fn manufacturer_data_value(entries: &[(u16, &[u8])]) -> Value<'static> {
+        let mut dictionary = Dict::new(&Signature::U16, &Signature::Variant);
+        for (id, data) in entries {
+            dictionary.append(Value::U16(*id), bytes(data)).unwrap();
+        }
+        Value::Dict(dictionary)
+    }
+
+    fn service_data_value(entries: &[(&str, &[u8])]) -> Value<'static> {
+        let mut dictionary = Dict::new(&Signature::Str, &Signature::Variant);
+        for (uuid, data) in entries {
+            dictionary
+                .append(Value::from(String::from(*uuid)), bytes(data))
+                .unwrap();
+        }
+        Value::Dict(dictionary)
+    }
+
+    macro_rules! test_decode_device_properties {
+        (
+            $(
+                test $name: ident {
+                    input: $properties: expr,
+                    result: $result: pat,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = decode_device_properties(&properties($properties));
+                    assert!(matches!(result, $result), "{result:?}");
+                }
+            )+
+        };
+    }
+
+    test_decode_device_properties! {
+        test device_properties {
+            input: vec![
+                ("Address", Value::from(ADDRESS)),
+                ("RSSI", Value::I16(-71)),
+                ("ManufacturerData", manufacturer_data_value(&[(0x004C, &[0x02]), (0x0499, &V5)])),
+                ("ServiceData", service_data_value(&[])),
+            ],
+            result: Ok(ScanResult {
+                rssi: Some(-71),
+                transport: Transport::ManufacturerData,
+                ..
+            }),
+        }
+
+        test properties_in_variants {
+            input: vec![
+                ("Address", variant(Value::from(ADDRESS))),
+                ("RSSI", variant(Value::I16(-71))),
+                ("ManufacturerData", variant(manufacturer_data_value(&[(0x0499, &V5)]))),
+            ],
+            result: Ok(ScanResult { rssi: Some(-71), .. }),
+        }
+
+        test device_properties_without_rssi {
+            input: vec![
+                ("Address", Value::from(ADDRESS)),
+                ("ServiceData", service_data_value(&[(EDDYSTONE_SERVICE, EDDYSTONE_V4)])),
+            ],
+            result: Ok(ScanResult { rssi: None, transport: Transport::EddystoneUrl, .. }),
+        }
+
+        test device_properties_without_data {
+            input: vec![("Address", Value::from(ADDRESS)), ("Name", Value::from("Ruuvi D6CB"))],
+            result: Err(PropertiesError::Decode(DecodeError::NoRuuviData)),
+        }
+
+        test missing_address {
+            input: vec![("ManufacturerData", manufacturer_data_value(&[(0x0499, &V5)]))],
+            result: Err(PropertiesError::InvalidProperty("Address")),
+        }
+
+        test invalid_address {
+            input: vec![
+                ("Address", Value::from("F4:1F:0C:28:CB")),
+                ("ManufacturerData", manufacturer_data_value(&[(0x0499, &V5)])),
+            ],
+            result: Err(PropertiesError::Address(ParseMacError)),
+        }
+
+        test invalid_rssi {
+            input: vec![
+                ("Address", Value::from(ADDRESS)),
+                ("RSSI", Value::from("-71")),
+            ],
+            result: Err(PropertiesError::InvalidProperty("RSSI")),
+        }
+
+        test invalid_manufacturer_data_type {
+            input: vec![
+                ("Address", Value::from(ADDRESS)),
+                ("ManufacturerData", Value::from(V5.to_vec())),
+            ],
+            result: Err(PropertiesError::InvalidProperty("ManufacturerData")),
+        }
+
+        test invalid_manufacturer_data_value {
+            input: vec![
+                ("Address", Value::from(ADDRESS)),
+                ("ManufacturerData", {
+                    let mut dictionary = Dict::new(&Signature::U16, &Signature::Variant);
+                    dictionary.append(Value::U16(0x0499), variant(Value::from("data"))).unwrap();
+                    Value::Dict(dictionary)
+                }),
+            ],
+            result: Err(PropertiesError::InvalidProperty("ManufacturerData")),
+        }
+
+        test invalid_service_data_key {
+            input: vec![
+                ("Address", Value::from(ADDRESS)),
+                ("ServiceData", manufacturer_data_value(&[(0xFEAA, EDDYSTONE_V4)])),
+            ],
+            result: Err(PropertiesError::InvalidProperty("ServiceData")),
+        }
+    }
+
+    #[test]
+    fn address_property() {
+        let result = decode_device_properties(&properties(vec![
+            ("Address", Value::from(ADDRESS)),
+            (
+                "ManufacturerData",
+                manufacturer_data_value(&[(0x0499, &V5)]),
+            ),
+        ]));
+
+        assert_eq!(
+            result.map(|result| result.address).ok(),
+            Some(ADDRESS.parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,214 @@
+/*!
+
+Decoding of RuuviTag advertisements found by [btleplug][1] scans.
+
+btleplug reports the manufacturer specific data and the service data of a peripheral in its
+[`PeripheralProperties`]. [`decode_properties`] picks the Ruuvi manufacturer specific data, or an
+Eddystone URL of the legacy data formats when the peripheral has no Ruuvi manufacturer data, and
+returns the decoded values with the address and the signal strength of the peripheral.
+
+On macOS, btleplug does not know the Bluetooth address of a peripheral and reports it as all
+zeros. In that case the MAC address in the payload is used instead, if the data format has one.
+
+On Linux, btleplug uses BlueZ over D-Bus and links to the system `libdbus`. Enable
+`vendored-dbus` feature to build `libdbus` from source instead.
+
+[1]: https://docs.rs/btleplug
+
+```rust
+use std::collections::HashMap;
+
+use btleplug::api::{BDAddr, PeripheralProperties};
+use ruuvi_sensor_protocol::{btleplug::decode_properties, Mac, Temperature};
+# use ruuvi_sensor_protocol::DecodeError;
+
+let properties = PeripheralProperties {
+    address: BDAddr::from([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]),
+    rssi: Some(-62),
+    manufacturer_data: HashMap::from([(
+        0x0499,
+        vec![0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86],
+    )]),
+    ..PeripheralProperties::default()
+};
+
+let result = decode_properties(&properties)?;
+
+assert_eq!(result.address, Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]));
+assert_eq!(result.rssi, Some(-62));
+assert_eq!(result.values.temperature_as_millicelsius(), Some(1690));
+# Ok::<(), DecodeError>(())
+```
+
+[`PeripheralProperties`]: ::btleplug::api::PeripheralProperties
+*/
+
+use ::btleplug::api::{bleuuid::uuid_from_u16, BDAddr, PeripheralProperties};
+
+use crate::{
+    decode::{DecodeError, ScanResult, MANUFACTURER_DATA_ID},
+    Mac, MacAddress,
+};
+
+/// 16-bit UUID of Eddystone service
+const EDDYSTONE_SERVICE: u16 = 0xFEAA;
+
+/// Decodes the Ruuvi data in the properties of a peripheral found by btleplug. If the address of
+/// the peripheral is all zeros, the MAC address in the payload is used when available.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::NoRuuviData`] if the properties contain neither Ruuvi manufacturer
+/// specific data nor an Eddystone URL of a tag, and [`DecodeError::Parse`] if the data is not
+/// valid.
+pub fn decode_properties(properties: &PeripheralProperties) -> Result<ScanResult, DecodeError> {
+    let mut result = ScanResult::decode(
+        Mac::new(properties.address.into_inner()),
+        properties.rssi,
+        properties
+            .manufacturer_data
+            .get(&MANUFACTURER_DATA_ID)
+            .map(Vec::as_slice),
+        properties
+            .service_data
+            .get(&uuid_from_u16(EDDYSTONE_SERVICE))
+            .map(Vec::as_slice),
+    )?;
+
+    if properties.address == BDAddr::default() {
+        if let Some(address) = result.values.mac_address() {
+            result.address = address;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{DataFormat, Transport};
+
+    const ADDRESS: [u8; 6] = [0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6];
+
+    const V5: [u8; 24] = [
+        0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+        0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    ];
+
+    const EDDYSTONE_V4: &[u8] = b"\x10\xF9\x03ruu.vi/#BEAYAMFsA";
+
+    fn properties(
+        manufacturer_data: &[(u16, &[u8])],
+        service_data: &[(u16, &[u8])],
+    ) -> PeripheralProperties {
+        PeripheralProperties {
+            address: BDAddr::from(ADDRESS),
+            rssi: Some(-71),
+            manufacturer_data: manufacturer_data
+                .iter()
+                .map(|(id, data)| (*id, data.to_vec()))
+                .collect(),
+            service_data: service_data
+                .iter()
+                .map(|(uuid, data)| (uuid_from_u16(*uuid), data.to_vec()))
+                .collect::<HashMap<_, _>>(),
+            ..PeripheralProperties::default()
+        }
+    }
+
+    macro_rules! test_decode_properties {
+        (
+            $(
+                test $name: ident {
+                    input: ($manufacturer_data: expr, $service_data: expr),
+                    result: $result: pat,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = decode_properties(&properties(
+                        &$manufacturer_data,
+                        &$service_data,
+                    ));
+                    assert!(matches!(result, $result), "{result:?}");
+                }
+            )+
+        };
+    }
+
+    test_decode_properties! {
+        test manufacturer_data {
+            input: ([(0x0499, &V5[..])], []),
+            result: Ok(ScanResult {
+                rssi: Some(-71),
+                transport: Transport::ManufacturerData,
+                ..
+            }),
+        }
+
+        test other_manufacturers_are_ignored {
+            input: ([(0x004C, &[0x02, 0x15][..]), (0x0499, &V5[..])], []),
+            result: Ok(ScanResult { transport: Transport::ManufacturerData, .. }),
+        }
+
+        test eddystone_url {
+            input: ([(0x004C, &[0x02, 0x15][..])], [(EDDYSTONE_SERVICE, EDDYSTONE_V4)]),
+            result: Ok(ScanResult { transport: Transport::EddystoneUrl, .. }),
+        }
+
+        test other_service_data_is_ignored {
+            input: ([], [(0x180F, &[0x64][..])]),
+            result: Err(DecodeError::NoRuuviData),
+        }
+
+        test invalid_manufacturer_data {
+            input: ([(0x0499, &V5[..4])], []),
+            result: Err(DecodeError::Parse(_)),
+        }
+    }
+
+    #[test]
+    fn address_and_values() {
+        let result = decode_properties(&properties(&[(0x0499, &V5)], &[])).ok();
+
+        assert_eq!(
+            result.as_ref().map(|result| result.address),
+            Some(Mac::new(ADDRESS))
+        );
+        assert_eq!(
+            result.and_then(|result| result.values.data_format()),
+            Some(DataFormat::V5)
+        );
+    }
+
+    #[test]
+    fn unknown_address_falls_back_to_payload() {
+        let mut properties = properties(&[(0x0499, &V5)], &[]);
+        properties.address = BDAddr::default();
+
+        assert_eq!(
+            decode_properties(&properties)
+                .ok()
+                .map(|result| result.address),
+            Some(Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F]))
+        );
+    }
+
+    #[test]
+    fn unknown_address_without_payload_address() {
+        let mut properties = properties(&[], &[(EDDYSTONE_SERVICE, EDDYSTONE_V4)]);
+        properties.address = BDAddr::default();
+
+        assert_eq!(
+            decode_properties(&properties)
+                .ok()
+                .map(|result| result.address),
+            Some(Mac::new([0; 6]))
+        );
+    }
+}
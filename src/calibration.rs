@@ -0,0 +1,584 @@
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, string::String};
+#[cfg(feature = "alloc")]
+use core::fmt::{self, Display};
+
+use crate::{
+    Acceleration, AccelerationVector, BatteryPotential, Humidity, Mac, MacAddress,
+    MeasurementSequenceNumber, MovementCounter, Pressure, Temperature, TransmitterPower,
+};
+
+/// A linear correction `value * scale + offset` applied to a measurement
+///
+/// The offset is in the unit of the corrected measurement: milli-Celsius for temperature, parts
+/// per million for humidity and pascals for pressure. Temperature is scaled in Celsius scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct Correction {
+    /// multiplier applied to the measured value
+    pub scale: f64,
+    /// offset added to the scaled value
+    pub offset: i32,
+}
+
+impl Correction {
+    /// Correction which does not change the value
+    pub const IDENTITY: Self = Self {
+        scale: 1.0,
+        offset: 0,
+    };
+
+    /// Returns a correction which only adds `offset` to the value
+    #[must_use]
+    pub const fn offset(offset: i32) -> Self {
+        Self { scale: 1.0, offset }
+    }
+
+    /// Returns a correction which multiplies the value with `scale` and then adds `offset`
+    #[must_use]
+    pub const fn linear(scale: f64, offset: i32) -> Self {
+        Self { scale, offset }
+    }
+
+    /// Applies the correction to a value, and rounds the result to the nearest integer
+    #[must_use]
+    pub fn apply(&self, value: i64) -> i64 {
+        #[expect(clippy::as_conversions, clippy::cast_precision_loss)]
+        let scaled = value as f64 * self.scale;
+        let rounded = if scaled < 0.0 {
+            scaled - 0.5
+        } else {
+            scaled + 0.5
+        };
+
+        #[expect(clippy::as_conversions, clippy::cast_possible_truncation)]
+        let rounded = rounded as i64;
+
+        rounded.saturating_add(i64::from(self.offset))
+    }
+}
+
+impl Default for Correction {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// Corrections for the environmental measurements of a single tag
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
+pub struct Calibration {
+    /// correction for temperature in milli-Celsius
+    pub temperature: Correction,
+    /// correction for relative humidity in parts per million
+    pub humidity: Correction,
+    /// correction for pressure in pascals
+    pub pressure: Correction,
+}
+
+impl Calibration {
+    /// Wraps `values` so that the calibration is applied to its measurements
+    pub fn apply<T>(self, values: T) -> Calibrated<T> {
+        Calibrated {
+            values,
+            calibration: self,
+        }
+    }
+}
+
+/// Values with calibration applied to temperature, humidity and pressure
+///
+/// Other measurements are passed through unchanged from the wrapped values.
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::{Calibration, Correction, SensorValues, Temperature};
+/// # use ruuvi_sensor_protocol::ParseError;
+///
+/// let id = 0x0499;
+/// let value = [
+///     0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+/// ];
+/// let values = SensorValues::from_manufacturer_specific_data(id, value)?;
+/// let calibration = Calibration {
+///     temperature: Correction::offset(-500),
+///     ..Calibration::default()
+/// };
+///
+/// assert_eq!(calibration.apply(values).temperature_as_millicelsius(), Some(1190));
+/// # Ok::<(), ParseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Calibrated<T> {
+    values: T,
+    calibration: Calibration,
+}
+
+impl<T> Calibrated<T> {
+    /// Returns the uncorrected values
+    pub fn uncorrected(&self) -> &T {
+        &self.values
+    }
+
+    /// Returns the calibration applied to the values
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+
+    /// Returns the uncorrected values
+    pub fn into_inner(self) -> T {
+        self.values
+    }
+}
+
+impl<T: Acceleration> Acceleration for Calibrated<T> {
+    fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
+        self.values.acceleration_vector_as_milli_g()
+    }
+}
+
+impl<T: BatteryPotential> BatteryPotential for Calibrated<T> {
+    fn battery_potential_as_millivolts(&self) -> Option<u16> {
+        self.values.battery_potential_as_millivolts()
+    }
+}
+
+impl<T: Humidity> Humidity for Calibrated<T> {
+    fn humidity_as_ppm(&self) -> Option<u32> {
+        let humidity = self.values.humidity_as_ppm()?;
+        let humidity = self.calibration.humidity.apply(i64::from(humidity));
+
+        Some(saturate(humidity))
+    }
+}
+
+impl<T: MacAddress> MacAddress for Calibrated<T> {
+    fn mac_address(&self) -> Option<Mac> {
+        self.values.mac_address()
+    }
+}
+
+impl<T: MeasurementSequenceNumber> MeasurementSequenceNumber for Calibrated<T> {
+    fn measurement_sequence_number(&self) -> Option<u32> {
+        self.values.measurement_sequence_number()
+    }
+}
+
+impl<T: MovementCounter> MovementCounter for Calibrated<T> {
+    fn movement_counter(&self) -> Option<u32> {
+        self.values.movement_counter()
+    }
+}
+
+impl<T: Pressure> Pressure for Calibrated<T> {
+    fn pressure_as_pascals(&self) -> Option<u32> {
+        let pressure = self.values.pressure_as_pascals()?;
+        let pressure = self.calibration.pressure.apply(i64::from(pressure));
+
+        Some(saturate(pressure))
+    }
+}
+
+impl<T: Temperature> Temperature for Calibrated<T> {
+    fn temperature_as_millikelvins(&self) -> Option<u32> {
+        let temperature = self.values.temperature_as_millicelsius()?;
+        let temperature = self.calibration.temperature.apply(i64::from(temperature));
+
+        Some(saturate(
+            temperature + i64::from(Self::ZERO_CELSIUS_IN_MILLIKELVINS),
+        ))
+    }
+}
+
+impl<T: TransmitterPower> TransmitterPower for Calibrated<T> {
+    fn tx_power_as_dbm(&self) -> Option<i8> {
+        self.values.tx_power_as_dbm()
+    }
+}
+
+fn saturate(value: i64) -> u32 {
+    u32::try_from(value.max(0)).unwrap_or(u32::MAX)
+}
+
+/// Calibrations of multiple tags keyed by their MAC addresses
+///
+/// With `serde` feature, the table can be deserialized from a map where keys are MAC addresses
+/// in `C8:25:2D:8E:9C:2C`, `C8-25-2D-8E-9C-2C` or `C8252D8E9C2C` format.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use ruuvi_sensor_protocol::{CalibrationTable, Correction, Mac};
+///
+/// let table: CalibrationTable = serde_json::from_str(
+///     r#"{
+///         "C8:25:2D:8E:9C:2C": {
+///             "temperature": { "offset": -500 },
+///             "humidity": { "scale": 1.02, "offset": 10000 }
+///         }
+///     }"#,
+/// )
+/// .unwrap();
+///
+/// let calibration = table.get(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C])).unwrap();
+/// assert_eq!(calibration.temperature, Correction::offset(-500));
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(try_from = "BTreeMap<String, Calibration>")
+)]
+pub struct CalibrationTable {
+    calibrations: BTreeMap<Mac, Calibration>,
+}
+
+#[cfg(feature = "alloc")]
+impl CalibrationTable {
+    /// Returns an empty table
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the calibration of a tag, and returns the previous calibration if there was one
+    pub fn insert(&mut self, mac_address: Mac, calibration: Calibration) -> Option<Calibration> {
+        self.calibrations.insert(mac_address, calibration)
+    }
+
+    /// Removes the calibration of a tag, and returns it if there was one
+    pub fn remove(&mut self, mac_address: Mac) -> Option<Calibration> {
+        self.calibrations.remove(&mac_address)
+    }
+
+    /// Returns the calibration of a tag
+    #[must_use]
+    pub fn get(&self, mac_address: Mac) -> Option<&Calibration> {
+        self.calibrations.get(&mac_address)
+    }
+
+    /// Applies the calibration of the tag which sent `values`. Values without a MAC address or
+    /// without a calibration in the table are wrapped with an identity calibration.
+    pub fn apply<T: MacAddress>(&self, values: T) -> Calibrated<T> {
+        let calibration = values
+            .mac_address()
+            .and_then(|mac_address| self.get(mac_address))
+            .copied()
+            .unwrap_or_default();
+
+        calibration.apply(values)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromIterator<(Mac, Calibration)> for CalibrationTable {
+    fn from_iter<I: IntoIterator<Item = (Mac, Calibration)>>(iter: I) -> Self {
+        Self {
+            calibrations: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<BTreeMap<String, Calibration>> for CalibrationTable {
+    type Error = InvalidMacAddress;
+
+    fn try_from(calibrations: BTreeMap<String, Calibration>) -> Result<Self, Self::Error> {
+        calibrations
+            .into_iter()
+            .map(|(mac_address, calibration)| match mac_address.parse() {
+                Ok(parsed) => Ok((parsed, calibration)),
+                Err(_) => Err(InvalidMacAddress(mac_address)),
+            })
+            .collect()
+    }
+}
+
+/// Error returned when a key of a calibration table is not a valid MAC address
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidMacAddress(pub String);
+
+#[cfg(feature = "alloc")]
+impl Display for InvalidMacAddress {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(formatter, "Invalid MAC address {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::error::Error for InvalidMacAddress {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::formats::testing::test_measurement_trait_methods;
+
+    struct Values;
+
+    impl Humidity for Values {
+        fn humidity_as_ppm(&self) -> Option<u32> {
+            Some(534_900)
+        }
+    }
+
+    impl MacAddress for Values {
+        fn mac_address(&self) -> Option<Mac> {
+            Some(MAC_ADDRESS)
+        }
+    }
+
+    impl Pressure for Values {
+        fn pressure_as_pascals(&self) -> Option<u32> {
+            Some(100_044)
+        }
+    }
+
+    impl Temperature for Values {
+        fn temperature_as_millikelvins(&self) -> Option<u32> {
+            Some(24_300 + 273_150)
+        }
+    }
+
+    struct NoValues;
+
+    impl Humidity for NoValues {
+        fn humidity_as_ppm(&self) -> Option<u32> {
+            None
+        }
+    }
+
+    impl Temperature for NoValues {
+        fn temperature_as_millikelvins(&self) -> Option<u32> {
+            None
+        }
+    }
+
+    const MAC_ADDRESS: Mac = Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]);
+
+    #[test]
+    fn calibration_has_default_traits() {
+        crate::testing::type_has_default_traits::<Calibration>();
+        crate::testing::type_has_default_traits::<Calibrated<Values>>();
+    }
+
+    macro_rules! test_correction {
+        (
+            $(
+                test $name: ident {
+                    correction: $correction: expr,
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($correction.apply($input), $result);
+                }
+            )+
+        };
+    }
+
+    test_correction! {
+        test identity {
+            correction: Correction::IDENTITY,
+            input: -1234,
+            result: -1234,
+        }
+
+        test positive_offset {
+            correction: Correction::offset(500),
+            input: 1000,
+            result: 1500,
+        }
+
+        test negative_offset {
+            correction: Correction::offset(-500),
+            input: 200,
+            result: -300,
+        }
+
+        test scale_rounds_up {
+            correction: Correction::linear(1.5, 0),
+            input: 5,
+            result: 8,
+        }
+
+        test negative_scale_rounds_down {
+            correction: Correction::linear(1.5, 0),
+            input: -5,
+            result: -8,
+        }
+
+        test linear {
+            correction: Correction::linear(1.02, -1000),
+            input: 534_900,
+            result: 544_598,
+        }
+    }
+
+    test_measurement_trait_methods! {
+        test identity_calibration {
+            values: Calibration::default().apply(Values),
+            expected: {
+                humidity_as_ppm: Some(534_900),
+                mac_address: Some(MAC_ADDRESS),
+                pressure_as_pascals: Some(100_044),
+                temperature_as_millicelsius: Some(24_300),
+            },
+        }
+
+        test offset_calibration {
+            values: Calibration {
+                temperature: Correction::offset(-1_500),
+                humidity: Correction::offset(20_000),
+                pressure: Correction::offset(-44),
+            }
+            .apply(Values),
+            expected: {
+                humidity_as_ppm: Some(554_900),
+                mac_address: Some(MAC_ADDRESS),
+                pressure_as_pascals: Some(100_000),
+                temperature_as_millicelsius: Some(22_800),
+            },
+        }
+
+        test temperature_below_zero {
+            values: Calibration {
+                temperature: Correction::offset(-30_000),
+                ..Calibration::default()
+            }
+            .apply(Values),
+            expected: {
+                temperature_as_millicelsius: Some(-5_700),
+            },
+        }
+
+        test temperature_is_scaled_in_celsius {
+            values: Calibration {
+                temperature: Correction::linear(0.5, 0),
+                ..Calibration::default()
+            }
+            .apply(Values),
+            expected: {
+                temperature_as_millicelsius: Some(12_150),
+            },
+        }
+
+        test saturated_humidity {
+            values: Calibration {
+                humidity: Correction::offset(-600_000),
+                ..Calibration::default()
+            }
+            .apply(Values),
+            expected: {
+                humidity_as_ppm: Some(0),
+            },
+        }
+
+        test unavailable_values {
+            values: Calibration {
+                temperature: Correction::offset(-1_500),
+                humidity: Correction::offset(20_000),
+                ..Calibration::default()
+            }
+            .apply(NoValues),
+            expected: {
+                humidity_as_ppm: None,
+                temperature_as_millicelsius: None,
+            },
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    mod table {
+        use super::*;
+
+        #[test]
+        fn calibration_table_has_default_traits() {
+            crate::testing::type_has_default_traits::<CalibrationTable>();
+        }
+
+        #[test]
+        fn apply_calibration_by_mac_address() {
+            let calibration = Calibration {
+                temperature: Correction::offset(-300),
+                ..Calibration::default()
+            };
+            let table: CalibrationTable = [(MAC_ADDRESS, calibration)].into_iter().collect();
+            let values = table.apply(Values);
+
+            assert_eq!(values.calibration(), &calibration);
+            assert_eq!(values.temperature_as_millicelsius(), Some(24_000));
+        }
+
+        #[test]
+        fn apply_missing_calibration() {
+            let mut table = CalibrationTable::new();
+            table.insert(Mac::new([0; 6]), Calibration::default());
+            let values = table.apply(Values);
+
+            assert_eq!(values.calibration(), &Calibration::default());
+            assert_eq!(values.temperature_as_millicelsius(), Some(24_300));
+        }
+
+        #[test]
+        fn invalid_mac_address_key() {
+            let calibrations = [(String::from("C8:25:2D"), Calibration::default())];
+            let result = CalibrationTable::try_from(BTreeMap::from(calibrations));
+
+            assert_eq!(result, Err(InvalidMacAddress(String::from("C8:25:2D"))));
+        }
+    }
+
+    #[cfg(all(feature = "alloc", feature = "serde"))]
+    #[expect(clippy::unwrap_used)]
+    mod deserialize {
+        use super::*;
+
+        #[test]
+        fn deserialize_calibration_table() {
+            let table: CalibrationTable = serde_json::from_str(
+                "{
+                    \"C8:25:2D:8E:9C:2C\": {
+                        \"temperature\": { \"offset\": -500 },
+                        \"humidity\": { \"scale\": 1.02, \"offset\": 10000 }
+                    },
+                    \"f41f0c28cbd6\": {
+                        \"pressure\": { \"offset\": 120 }
+                    }
+                }",
+            )
+            .unwrap();
+
+            assert_eq!(
+                table.get(MAC_ADDRESS),
+                Some(&Calibration {
+                    temperature: Correction::offset(-500),
+                    humidity: Correction::linear(1.02, 10_000),
+                    pressure: Correction::IDENTITY,
+                })
+            );
+            assert_eq!(
+                table.get(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])),
+                Some(&Calibration {
+                    pressure: Correction::offset(120),
+                    ..Calibration::default()
+                })
+            );
+        }
+
+        #[test]
+        fn deserialize_invalid_mac_address() {
+            let result: Result<CalibrationTable, _> = serde_json::from_str("{ \"C8:25:2D\": {} }");
+
+            result.unwrap_err();
+        }
+    }
+}
@@ -0,0 +1,68 @@
+/// Scales a decimal value and rounds it to the nearest integer, returning `None` if the result is
+/// not finite or does not fit in `T`
+pub(crate) fn scale<T: TryFrom<i64>>(value: f64, scale: f64) -> Option<T> {
+    let value = value * scale;
+    let value = if value < 0.0 {
+        value - 0.5
+    } else {
+        value + 0.5
+    };
+
+    if value.is_finite() {
+        #[expect(clippy::as_conversions, clippy::cast_possible_truncation)]
+        let value = value as i64;
+        T::try_from(value).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_scale {
+        (
+            $(
+                test $name: ident {
+                    input: ($value: expr, $scale: expr),
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(scale::<i16>($value, $scale), $result);
+                }
+            )+
+        };
+    }
+
+    test_scale! {
+        test rounds_half_up {
+            input: (2.5, 1.0),
+            result: Some(3),
+        }
+
+        test rounds_negative_half_down {
+            input: (-2.5, 1.0),
+            result: Some(-3),
+        }
+
+        test out_of_range {
+            input: (40.0, 1000.0),
+            result: None,
+        }
+
+        test not_a_number {
+            input: (f64::NAN, 1.0),
+            result: None,
+        }
+
+        test infinite {
+            input: (f64::INFINITY, 1.0),
+            result: None,
+        }
+    }
+}
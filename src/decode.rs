@@ -0,0 +1,515 @@
+use core::fmt::{self, Display};
+
+use crate::{
+    advertisement::{IterPackets, Packet},
+    errors::ParseError,
+    DataFormat, Mac, MacAddress, SensorValues,
+};
+
+pub(crate) const MANUFACTURER_DATA_ID: u16 = 0x0499;
+const EDDYSTONE_UUID: [u8; 2] = [0xAA, 0xFE];
+const EDDYSTONE_URL_FRAME: u8 = 0x10;
+const SERVICE_DATA_TYPE: u8 = 0x16;
+const RUUVI_URL_PREFIX: &[u8] = b"ruu.vi/#";
+
+/// Encoded sensor values in one of the ways they are transported from a tag
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecodeInput<'a> {
+    /// Manufacturer specific data with its manufacturer id
+    ManufacturerData {
+        /// Manufacturer id of the data
+        id: u16,
+        /// Value of the data, starting with the format version
+        data: &'a [u8],
+    },
+    /// Eddystone service data starting with the frame type, without the service UUID
+    EddystoneServiceData(&'a [u8]),
+    /// Raw Bluetooth advertisement data consisting of length-prefixed packets
+    Advertisement(&'a [u8]),
+    /// MQTT message payload published by Ruuvi Gateway
+    #[cfg(feature = "gateway")]
+    GatewayJson(&'a [u8]),
+    /// Notification sent over Nordic UART Service
+    NusNotification(&'a [u8]),
+}
+
+/// Way the sensor values were transported from a tag
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Transport {
+    /// Manufacturer specific data of an advertisement
+    ManufacturerData,
+    /// Eddystone URL of an advertisement
+    EddystoneUrl,
+    /// MQTT message payload published by Ruuvi Gateway
+    Gateway,
+    /// Notification sent over Nordic UART Service
+    Nus,
+}
+
+/// Sensor values decoded with [`decode`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Decoded {
+    /// Decoded sensor values
+    pub values: SensorValues,
+    /// Way the sensor values were transported
+    pub transport: Transport,
+}
+
+impl Decoded {
+    /// Returns the data format the values were decoded from, if known.
+    #[must_use]
+    pub fn data_format(&self) -> Option<DataFormat> {
+        self.values.data_format()
+    }
+}
+
+/// Sensor values decoded from an advertisement received by a Bluetooth scanner, with the address
+/// of the tag and the received signal strength
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScanResult {
+    /// Bluetooth address of the tag
+    pub address: Mac,
+    /// Received signal strength in dBm, if reported by the scanner
+    pub rssi: Option<i16>,
+    /// Decoded sensor values
+    pub values: SensorValues,
+    /// Way the sensor values were transported
+    pub transport: Transport,
+}
+
+#[cfg(any(feature = "bluez", feature = "btleplug", test))]
+impl ScanResult {
+    /// Decodes the Ruuvi manufacturer specific data if it is present, and the Eddystone service
+    /// data otherwise
+    pub(crate) fn decode(
+        address: Mac,
+        rssi: Option<i16>,
+        manufacturer_data: Option<&[u8]>,
+        eddystone_service_data: Option<&[u8]>,
+    ) -> Result<Self, DecodeError> {
+        let Decoded { values, transport } = match (manufacturer_data, eddystone_service_data) {
+            (Some(data), _) => decode(DecodeInput::ManufacturerData {
+                id: MANUFACTURER_DATA_ID,
+                data,
+            })?,
+            (None, Some(data)) => decode(DecodeInput::EddystoneServiceData(data))?,
+            (None, None) => return Err(DecodeError::NoRuuviData),
+        };
+
+        Ok(Self {
+            address,
+            rssi,
+            values,
+            transport,
+        })
+    }
+}
+
+impl MacAddress for ScanResult {
+    fn mac_address(&self) -> Option<Mac> {
+        Some(self.address)
+    }
+}
+
+/// Errors which can occur when decoding sensor values with [`decode`]
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Ruuvi data was found but could not be parsed
+    Parse(ParseError),
+    /// Input does not contain a Ruuvi manufacturer data or a Ruuvi Eddystone URL
+    NoRuuviData,
+    /// Advertisement contains a packet which is longer than the remaining data
+    InvalidAdvertisement,
+    /// Advertising data of an extended advertising report is incomplete or truncated
+    IncompleteAdvertisement,
+    /// MQTT message payload could not be parsed
+    #[cfg(feature = "gateway")]
+    Gateway(serde_json::Error),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            DecodeError::Parse(error) => write!(formatter, "Invalid Ruuvi data: {error}"),
+            DecodeError::NoRuuviData => write!(formatter, "No Ruuvi data found"),
+            DecodeError::InvalidAdvertisement => write!(formatter, "Invalid advertisement data"),
+            DecodeError::IncompleteAdvertisement => {
+                write!(formatter, "Incomplete advertisement data")
+            }
+            #[cfg(feature = "gateway")]
+            DecodeError::Gateway(error) => write!(formatter, "Invalid MQTT payload: {error}"),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            DecodeError::Parse(error) => Some(error),
+            #[cfg(feature = "gateway")]
+            DecodeError::Gateway(error) => Some(error),
+            DecodeError::NoRuuviData
+            | DecodeError::InvalidAdvertisement
+            | DecodeError::IncompleteAdvertisement => None,
+        }
+    }
+}
+
+impl From<ParseError> for DecodeError {
+    fn from(error: ParseError) -> Self {
+        DecodeError::Parse(error)
+    }
+}
+
+/// Decodes sensor values from any of the ways they are transported from a tag, and reports which
+/// data format and transport were used.
+///
+/// Manufacturer specific data is parsed like in [`SensorValues::from_manufacturer_specific_data`]
+/// and Eddystone URLs of data formats 2 and 4 are parsed from the `ruu.vi/#<payload>` form. When
+/// an advertisement contains both, the manufacturer specific data is used.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::NoRuuviData`] if the input does not contain Ruuvi data,
+/// [`DecodeError::InvalidAdvertisement`] if the packets of an advertisement can not be read, and
+/// [`DecodeError::Parse`] if Ruuvi data is found but it is not valid.
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::{
+///     decode, DataFormat, DecodeError, DecodeInput, Temperature, Transport,
+/// };
+///
+/// let advertisement = [
+///     0x02, 0x01, 0x06, 0x03, 0x03, 0xAA, 0xFE, 0x16, 0x16, 0xAA, 0xFE, 0x10, 0xF9, 0x03, b'r',
+///     b'u', b'u', b'.', b'v', b'i', b'/', b'#', b'B', b'E', b'A', b'Y', b'A', b'M', b'F', b's',
+/// ];
+///
+/// let decoded = decode(DecodeInput::Advertisement(&advertisement))?;
+/// assert_eq!(decoded.transport, Transport::EddystoneUrl);
+/// assert_eq!(decoded.data_format(), Some(DataFormat::V4));
+/// assert_eq!(decoded.values.temperature_as_millicelsius(), Some(24_000));
+/// # Ok::<(), DecodeError>(())
+/// ```
+pub fn decode(input: DecodeInput<'_>) -> Result<Decoded, DecodeError> {
+    match input {
+        DecodeInput::ManufacturerData { id, data } => Ok(Decoded {
+            values: SensorValues::from_manufacturer_specific_data(id, data)?,
+            transport: Transport::ManufacturerData,
+        }),
+        DecodeInput::EddystoneServiceData(data) => Ok(Decoded {
+            values: decode_eddystone(data)?,
+            transport: Transport::EddystoneUrl,
+        }),
+        DecodeInput::Advertisement(data) => decode_advertisement(data),
+        #[cfg(feature = "gateway")]
+        DecodeInput::GatewayJson(data) => {
+            let payload: crate::gateway::MqttData =
+                serde_json::from_slice(data).map_err(DecodeError::Gateway)?;
+            Ok(Decoded {
+                values: payload.data,
+                transport: Transport::Gateway,
+            })
+        }
+        DecodeInput::NusNotification(data) => Ok(Decoded {
+            values: SensorValues::from_nus_notification(data)?,
+            transport: Transport::Nus,
+        }),
+    }
+}
+
+fn decode_eddystone(data: &[u8]) -> Result<SensorValues, DecodeError> {
+    match data {
+        [EDDYSTONE_URL_FRAME, _tx_power, _scheme, url @ ..] => {
+            let fragment = url
+                .strip_prefix(RUUVI_URL_PREFIX)
+                .ok_or(DecodeError::NoRuuviData)?;
+            Ok(SensorValues::from_url_fragment(fragment)?)
+        }
+        _ => Err(DecodeError::NoRuuviData),
+    }
+}
+
+fn decode_advertisement(data: &[u8]) -> Result<Decoded, DecodeError> {
+    let mut eddystone = None;
+
+    for packet in IterPackets::new(data) {
+        match packet.map_err(|_| DecodeError::InvalidAdvertisement)? {
+            Packet::ManufacturerData(MANUFACTURER_DATA_ID, data) => {
+                return decode(DecodeInput::ManufacturerData {
+                    id: MANUFACTURER_DATA_ID,
+                    data,
+                });
+            }
+            Packet::Other(SERVICE_DATA_TYPE, data) => {
+                if let Some(data) = data.strip_prefix(&EDDYSTONE_UUID) {
+                    eddystone = eddystone.or(Some(data));
+                }
+            }
+            Packet::ManufacturerData(..) | Packet::Other(..) => {}
+        }
+    }
+
+    decode(DecodeInput::EddystoneServiceData(
+        eddystone.ok_or(DecodeError::NoRuuviData)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Temperature;
+
+    const V5: [u8; 24] = [
+        0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+        0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    ];
+
+    const EDDYSTONE_V4: &[u8] = b"\x10\xF9\x03ruu.vi/#BEAYAMFsA";
+
+    const EDDYSTONE_V2: &[u8] = b"\x10\xF9\x03ruu.vi/#AjQKMg-_";
+
+    /// Flags, Eddystone service data of data format 4 and manufacturer data of data format 5
+    const ADVERTISEMENT: &[u8] = b"\x02\x01\x06\x17\x16\xAA\xFE\x10\xF9\x03ruu.vi/#BEAYAMFsA\
+        \x1B\xFF\x99\x04\x05\x12\xFC\x53\x94\xC3\x7C\x00\x04\xFF\xFC\x04\x0C\xAC\x36\x42\x00\
+        \xCD\xCB\xB8\x33\x4C\x88\x4F";
+
+    /// Flags and Eddystone service data of data format 2
+    const EDDYSTONE_ADVERTISEMENT: &[u8] =
+        b"\x02\x01\x06\x16\x16\xAA\xFE\x10\xF9\x03ruu.vi/#AjQKMg-_";
+
+    #[test]
+    fn decoded_has_default_traits() {
+        crate::testing::type_has_default_traits::<Decoded>();
+    }
+
+    #[test]
+    fn decode_input_has_default_traits() {
+        crate::testing::type_has_default_traits::<DecodeInput<'_>>();
+    }
+
+    #[test]
+    fn transport_has_default_traits() {
+        crate::testing::type_has_default_traits::<Transport>();
+    }
+
+    #[test]
+    fn scan_result_has_default_traits() {
+        crate::testing::type_has_default_traits::<ScanResult>();
+    }
+
+    #[test]
+    fn decode_error_has_default_traits() {
+        crate::testing::type_has_default_traits::<DecodeError>();
+    }
+
+    macro_rules! test_decode {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: pat,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = decode($input);
+                    assert!(matches!(result, $result), "{result:?}");
+                }
+            )+
+        };
+    }
+
+    macro_rules! test_decoded_values {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    transport: $transport: expr,
+                    data_format: $data_format: expr,
+                    temperature: $temperature: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let decoded = decode($input).ok();
+
+                    assert_eq!(decoded.as_ref().map(|decoded| decoded.transport), Some($transport));
+                    assert_eq!(decoded.as_ref().and_then(Decoded::data_format), Some($data_format));
+                    assert_eq!(
+                        decoded.and_then(|decoded| decoded.values.temperature_as_millicelsius()),
+                        Some($temperature)
+                    );
+                }
+            )+
+        };
+    }
+
+    test_decoded_values! {
+        test manufacturer_data {
+            input: DecodeInput::ManufacturerData { id: MANUFACTURER_DATA_ID, data: &V5 },
+            transport: Transport::ManufacturerData,
+            data_format: DataFormat::V5,
+            temperature: 24_300,
+        }
+
+        test eddystone_v4 {
+            input: DecodeInput::EddystoneServiceData(EDDYSTONE_V4),
+            transport: Transport::EddystoneUrl,
+            data_format: DataFormat::V4,
+            temperature: 24_000,
+        }
+
+        test eddystone_v2 {
+            input: DecodeInput::EddystoneServiceData(EDDYSTONE_V2),
+            transport: Transport::EddystoneUrl,
+            data_format: DataFormat::V2,
+            temperature: 10_500,
+        }
+
+        test advertisement_prefers_manufacturer_data {
+            input: DecodeInput::Advertisement(ADVERTISEMENT),
+            transport: Transport::ManufacturerData,
+            data_format: DataFormat::V5,
+            temperature: 24_300,
+        }
+
+        test advertisement_with_eddystone_url {
+            input: DecodeInput::Advertisement(EDDYSTONE_ADVERTISEMENT),
+            transport: Transport::EddystoneUrl,
+            data_format: DataFormat::V2,
+            temperature: 10_500,
+        }
+
+        test nus_notification {
+            input: DecodeInput::NusNotification(&V5),
+            transport: Transport::Nus,
+            data_format: DataFormat::V5,
+            temperature: 24_300,
+        }
+    }
+
+    test_decode! {
+        test invalid_manufacturer_data {
+            input: DecodeInput::ManufacturerData { id: MANUFACTURER_DATA_ID, data: &V5[..10] },
+            result: Err(DecodeError::Parse(ParseError::InvalidValueLength(5, 10, 24))),
+        }
+
+        test eddystone_other_url {
+            input: DecodeInput::EddystoneServiceData(b"\x10\xF9\x03example.com/#BEAYAMFs"),
+            result: Err(DecodeError::NoRuuviData),
+        }
+
+        test eddystone_other_frame {
+            input: DecodeInput::EddystoneServiceData(&[0x00, 0xF9, 0x00]),
+            result: Err(DecodeError::NoRuuviData),
+        }
+
+        test eddystone_invalid_encoding {
+            input: DecodeInput::EddystoneServiceData(b"\x10\xF9\x03ruu.vi/#BEAY!MFs"),
+            result: Err(DecodeError::Parse(ParseError::InvalidEncoding)),
+        }
+
+        test eddystone_unsupported_format {
+            input: DecodeInput::EddystoneServiceData(b"\x10\xF9\x03ruu.vi/#A0AYAMFs"),
+            result: Err(DecodeError::Parse(ParseError::UnsupportedFormatVersion(3))),
+        }
+
+        test advertisement_without_ruuvi_data {
+            input: DecodeInput::Advertisement(&[0x02, 0x01, 0x06, 0x03, 0xFF, 0x4C, 0x00]),
+            result: Err(DecodeError::NoRuuviData),
+        }
+
+        test invalid_advertisement {
+            input: DecodeInput::Advertisement(&[0x02, 0x01, 0x06, 0x05, 0xFF, 0x99]),
+            result: Err(DecodeError::InvalidAdvertisement),
+        }
+    }
+
+    #[cfg(feature = "gateway")]
+    test_decode! {
+        test gateway_json {
+            input: DecodeInput::GatewayJson(
+                b"{\"data\": \"02010611FF990403170145355803E804E705E60886\"}",
+            ),
+            result: Ok(Decoded { transport: Transport::Gateway, .. }),
+        }
+
+        test gateway_invalid_json {
+            input: DecodeInput::GatewayJson(b"{\"data\": 1}"),
+            result: Err(DecodeError::Gateway(_)),
+        }
+    }
+
+    const ADDRESS: Mac = Mac::new([0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88]);
+
+    macro_rules! test_scan_result {
+        (
+            $(
+                test $name: ident {
+                    input: ($manufacturer_data: expr, $eddystone_service_data: expr),
+                    result: $result: pat,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = ScanResult::decode(
+                        ADDRESS,
+                        Some(-60),
+                        $manufacturer_data,
+                        $eddystone_service_data,
+                    );
+                    assert!(matches!(result, $result), "{result:?}");
+                }
+            )+
+        };
+    }
+
+    test_scan_result! {
+        test scan_result_prefers_manufacturer_data {
+            input: (Some(&V5), Some(EDDYSTONE_V4)),
+            result: Ok(ScanResult {
+                address: ADDRESS,
+                rssi: Some(-60),
+                transport: Transport::ManufacturerData,
+                ..
+            }),
+        }
+
+        test scan_result_eddystone_service_data {
+            input: (None, Some(EDDYSTONE_V2)),
+            result: Ok(ScanResult { transport: Transport::EddystoneUrl, .. }),
+        }
+
+        test scan_result_invalid_manufacturer_data {
+            input: (Some(&[0x05, 0x12]), Some(EDDYSTONE_V2)),
+            result: Err(DecodeError::Parse(_)),
+        }
+
+        test scan_result_without_ruuvi_data {
+            input: (None, None),
+            result: Err(DecodeError::NoRuuviData),
+        }
+    }
+
+    #[test]
+    fn scan_result_mac_address() {
+        let result = ScanResult::decode(ADDRESS, None, Some(&V5), None).ok();
+
+        assert_eq!(
+            result.as_ref().and_then(MacAddress::mac_address),
+            Some(ADDRESS)
+        );
+    }
+}
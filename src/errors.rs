@@ -14,6 +14,13 @@ pub enum ParseError {
     InvalidValueLength(u8, usize, usize),
     /// Format can not be determined from value due to it being empty
     EmptyValue,
+    /// Encrypted payload could not be decrypted with the given key
+    DecryptionFailed,
+    /// Checksum of the decrypted payload does not match, contains the received and the computed
+    /// checksum
+    InvalidChecksum(u8, u8),
+    /// Payload of an Eddystone URL is not valid base64
+    InvalidEncoding,
 }
 
 impl Display for ParseError {
@@ -32,6 +39,19 @@ impl Display for ParseError {
                 "Invalid data length of {length} for format version {version}, expected {expected}"
             ),
             ParseError::EmptyValue => write!(formatter, "Empty value, expected at least one byte"),
+            ParseError::DecryptionFailed => {
+                write!(formatter, "Decryption failed, the key is probably wrong")
+            }
+            ParseError::InvalidChecksum(received, computed) => write!(
+                formatter,
+                "Invalid checksum {received:#04X}, expected {computed:#04X}"
+            ),
+            ParseError::InvalidEncoding => {
+                write!(
+                    formatter,
+                    "Invalid encoding, expected base64 encoded payload"
+                )
+            }
         }
     }
 }
@@ -0,0 +1,112 @@
+use crate::errors::ParseError;
+
+/// Ruuvi data format which sensor values were parsed from
+///
+/// Data formats differ in which measurements they contain and in the resolution of the
+/// measurements, so the format tells for example which firmware generation sent the values.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DataFormat {
+    /// Data format 2, broadcast as an Eddystone URL
+    V2,
+    /// Data format 3, also known as `RAWv1`
+    V3,
+    /// Data format 4, broadcast as an Eddystone URL with a tag id
+    V4,
+    /// Data format 5, also known as `RAWv2`
+    V5,
+    /// Data format 8, encrypted
+    V8,
+}
+
+impl DataFormat {
+    /// Returns the version number of the data format, which is the first byte of the payload
+    #[must_use]
+    pub const fn version(self) -> u8 {
+        match self {
+            DataFormat::V2 => 2,
+            DataFormat::V3 => 3,
+            DataFormat::V4 => 4,
+            DataFormat::V5 => 5,
+            DataFormat::V8 => 8,
+        }
+    }
+}
+
+impl TryFrom<u8> for DataFormat {
+    type Error = ParseError;
+
+    fn try_from(version: u8) -> Result<Self, Self::Error> {
+        match version {
+            2 => Ok(DataFormat::V2),
+            3 => Ok(DataFormat::V3),
+            4 => Ok(DataFormat::V4),
+            5 => Ok(DataFormat::V5),
+            8 => Ok(DataFormat::V8),
+            version => Err(ParseError::UnsupportedFormatVersion(version)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_format_has_default_traits() {
+        crate::testing::type_has_default_traits::<DataFormat>();
+    }
+
+    macro_rules! test_try_from_version {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = DataFormat::try_from($input);
+                    let expected: Result<DataFormat, ParseError> = $result;
+                    assert_eq!(result, expected);
+                    assert_eq!(result.map(DataFormat::version), expected.map(|_| $input));
+                }
+            )+
+        };
+    }
+
+    test_try_from_version! {
+        test version_2 {
+            input: 2,
+            result: Ok(DataFormat::V2),
+        }
+
+        test version_3 {
+            input: 3,
+            result: Ok(DataFormat::V3),
+        }
+
+        test version_4 {
+            input: 4,
+            result: Ok(DataFormat::V4),
+        }
+
+        test version_5 {
+            input: 5,
+            result: Ok(DataFormat::V5),
+        }
+
+        test version_8 {
+            input: 8,
+            result: Ok(DataFormat::V8),
+        }
+
+        test unsupported_version {
+            input: 6,
+            result: Err(ParseError::UnsupportedFormatVersion(6)),
+        }
+    }
+}
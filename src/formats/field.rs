@@ -0,0 +1,302 @@
+use core::{
+    array,
+    fmt::{self, Display},
+};
+
+use crate::{
+    formats::{
+        traits::{
+            Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
+            MovementCounter, Pressure, Temperature, TransmitterPower,
+        },
+        AccelerationVector,
+    },
+    mac::Mac,
+};
+
+/// Identifies a single field of the values read from a RuuviTag
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Field {
+    /// Three-dimensional acceleration, see [`Acceleration`](crate::Acceleration)
+    Acceleration,
+    /// Battery potential, see [`BatteryPotential`](crate::BatteryPotential)
+    BatteryPotential,
+    /// Relative humidity, see [`Humidity`](crate::Humidity)
+    Humidity,
+    /// MAC address of the sensor, see [`MacAddress`](crate::MacAddress)
+    MacAddress,
+    /// Measurement sequence number, see
+    /// [`MeasurementSequenceNumber`](crate::MeasurementSequenceNumber)
+    MeasurementSequenceNumber,
+    /// Movement counter, see [`MovementCounter`](crate::MovementCounter)
+    MovementCounter,
+    /// Air pressure, see [`Pressure`](crate::Pressure)
+    Pressure,
+    /// Temperature, see [`Temperature`](crate::Temperature)
+    Temperature,
+    /// Transmitter power, see [`TransmitterPower`](crate::TransmitterPower)
+    TransmitterPower,
+}
+
+impl Field {
+    /// All fields in the order of their declaration
+    pub const ALL: [Self; 9] = [
+        Self::Acceleration,
+        Self::BatteryPotential,
+        Self::Humidity,
+        Self::MacAddress,
+        Self::MeasurementSequenceNumber,
+        Self::MovementCounter,
+        Self::Pressure,
+        Self::Temperature,
+        Self::TransmitterPower,
+    ];
+
+    /// Returns the name of the field in `snake_case`
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Acceleration => "acceleration",
+            Self::BatteryPotential => "battery_potential",
+            Self::Humidity => "humidity",
+            Self::MacAddress => "mac_address",
+            Self::MeasurementSequenceNumber => "measurement_sequence_number",
+            Self::MovementCounter => "movement_counter",
+            Self::Pressure => "pressure",
+            Self::Temperature => "temperature",
+            Self::TransmitterPower => "tx_power",
+        }
+    }
+
+    /// Returns the value of the field if it is available. The value is in the unit of the
+    /// corresponding measurement trait, so temperature is in milli-kelvins.
+    pub fn value<T>(self, values: &T) -> Option<Value>
+    where
+        T: Acceleration
+            + BatteryPotential
+            + Humidity
+            + MacAddress
+            + MeasurementSequenceNumber
+            + MovementCounter
+            + Pressure
+            + Temperature
+            + TransmitterPower,
+    {
+        match self {
+            Self::Acceleration => values.acceleration_vector_as_milli_g().map(Value::Vector),
+            Self::BatteryPotential => values
+                .battery_potential_as_millivolts()
+                .map(|value| Value::Unsigned(value.into())),
+            Self::Humidity => values.humidity_as_ppm().map(Value::Unsigned),
+            Self::MacAddress => values.mac_address().map(Value::MacAddress),
+            Self::MeasurementSequenceNumber => {
+                values.measurement_sequence_number().map(Value::Unsigned)
+            }
+            Self::MovementCounter => values.movement_counter().map(Value::Unsigned),
+            Self::Pressure => values.pressure_as_pascals().map(Value::Unsigned),
+            Self::Temperature => values.temperature_as_millikelvins().map(Value::Unsigned),
+            Self::TransmitterPower => values
+                .tx_power_as_dbm()
+                .map(|value| Value::Signed(value.into())),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        formatter.write_str(self.name())
+    }
+}
+
+/// Value of a single field, see [`Field::value`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Value {
+    /// Unsigned measurement, counter or sequence number
+    Unsigned(u32),
+    /// Signed measurement
+    Signed(i32),
+    /// Three-dimensional vector
+    Vector(AccelerationVector),
+    /// MAC address
+    MacAddress(Mac),
+}
+
+/// Iterator over the available fields and their values, see [`Fields::new`]
+#[derive(Clone, Debug)]
+pub struct Fields<'a, T> {
+    values: &'a T,
+    fields: array::IntoIter<Field, 9>,
+}
+
+impl<'a, T> Fields<'a, T>
+where
+    T: Acceleration
+        + BatteryPotential
+        + Humidity
+        + MacAddress
+        + MeasurementSequenceNumber
+        + MovementCounter
+        + Pressure
+        + Temperature
+        + TransmitterPower,
+{
+    /// Returns an iterator over the available fields of any type implementing all measurement
+    /// traits. Fields are yielded in the order of [`Field::ALL`] and unavailable fields are
+    /// skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ruuvi_sensor_protocol::{Field, Fields, SensorValues, Value};
+    /// # use ruuvi_sensor_protocol::ParseError;
+    ///
+    /// let value = [
+    ///     0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+    /// ];
+    /// let values = SensorValues::from_manufacturer_specific_data(0x0499, value)?;
+    /// let mut fields = Fields::new(&values);
+    ///
+    /// assert_eq!(fields.nth(1), Some((Field::BatteryPotential, Value::Unsigned(2182))));
+    /// assert_eq!(fields.count(), 3);
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    #[must_use]
+    pub fn new(values: &'a T) -> Self {
+        Self {
+            values,
+            fields: Field::ALL.into_iter(),
+        }
+    }
+}
+
+impl<T> Iterator for Fields<'_, T>
+where
+    T: Acceleration
+        + BatteryPotential
+        + Humidity
+        + MacAddress
+        + MeasurementSequenceNumber
+        + MovementCounter
+        + Pressure
+        + Temperature
+        + TransmitterPower,
+{
+    type Item = (Field, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let values = self.values;
+        self.fields
+            .find_map(|field| field.value(values).map(|value| (field, value)))
+    }
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_has_default_traits() {
+        crate::testing::type_has_default_traits::<Field>();
+    }
+
+    #[test]
+    fn value_has_default_traits() {
+        crate::testing::type_has_default_traits::<Value>();
+    }
+
+    #[test]
+    fn fields_has_default_traits() {
+        crate::testing::type_has_default_traits::<Fields<'_, crate::SensorValues>>();
+    }
+
+    #[test]
+    fn all_fields_are_sorted() {
+        assert!(Field::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    fn parse(value: &[u8]) -> crate::SensorValues {
+        crate::SensorValues::from_manufacturer_specific_data(0x0499, value).unwrap()
+    }
+
+    #[test]
+    fn fields_of_v5() {
+        let values = parse(&[
+            0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC,
+            0x36, 0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+        ]);
+        let mut fields = Fields::new(&values);
+
+        assert_eq!(
+            fields.next(),
+            Some((
+                Field::Acceleration,
+                Value::Vector(AccelerationVector(4, -4, 1036))
+            ))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((Field::BatteryPotential, Value::Unsigned(2977)))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((Field::Humidity, Value::Unsigned(534_900)))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((
+                Field::MacAddress,
+                Value::MacAddress(Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F]))
+            ))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((Field::MeasurementSequenceNumber, Value::Unsigned(205)))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((Field::MovementCounter, Value::Unsigned(66)))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((Field::Pressure, Value::Unsigned(100_044)))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((Field::Temperature, Value::Unsigned(297_450)))
+        );
+        assert_eq!(
+            fields.next(),
+            Some((Field::TransmitterPower, Value::Signed(4)))
+        );
+        assert_eq!(fields.next(), None);
+    }
+
+    #[test]
+    fn fields_skip_unavailable_values() {
+        let values = parse(&[
+            0x05, 0x80, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 0x80, 0x00, 0x80, 0x00, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ]);
+
+        assert_eq!(Fields::new(&values).next(), None);
+    }
+
+    #[test]
+    fn fields_of_v3() {
+        let values = parse(&[
+            0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+        ]);
+        let mut fields = Fields::new(&values).map(|(field, _)| field);
+
+        assert!(fields.by_ref().eq([
+            Field::Acceleration,
+            Field::BatteryPotential,
+            Field::Humidity,
+            Field::Pressure,
+            Field::Temperature,
+        ]));
+    }
+}
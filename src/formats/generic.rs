@@ -1,12 +1,18 @@
+use core::cmp::Ordering;
+
+#[cfg(feature = "v8")]
+use crate::formats::v8;
 use crate::{
     errors::ParseError,
     formats::{
+        parser::{Leniency, ParseMode},
         traits::{
             Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
             MovementCounter, Pressure, ProtocolPayload, Temperature, TransmitterPower,
         },
-        v3, v5, AccelerationVector,
+        url, v3, v5, AccelerationVector, DataFormat, Field, FieldMetadata, Fields,
     },
+    mac::Mac,
 };
 
 /// Represents a set of values read from sensors on the device
@@ -29,7 +35,9 @@ pub struct SensorValues {
     /// measurement sequence number
     measurement_sequence_number: Option<u32>,
     /// MAC address
-    mac_address: Option<[u8; 6]>,
+    mac_address: Option<Mac>,
+    /// data format the values were parsed from
+    data_format: Option<DataFormat>,
 }
 
 const MANUFACTURER_DATA_ID: u16 = 0x0499;
@@ -60,13 +68,227 @@ impl SensorValues {
     pub fn from_manufacturer_specific_data(
         id: u16,
         value: impl AsRef<[u8]>,
+    ) -> Result<Self, ParseError> {
+        Self::parse(id, value.as_ref(), ParseMode::Strict).map(|(values, _)| values)
+    }
+
+    /// Parses sensor values from the payload encoded in manufacturer specific data -field,
+    /// decrypting data format 8 payloads with the given key. Other formats are parsed like in
+    /// [`SensorValues::from_manufacturer_specific_data`], so the same function can be used for
+    /// tags broadcasting both encrypted and unencrypted formats.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`SensorValues::from_manufacturer_specific_data`], the
+    /// function returns [`ParseError::DecryptionFailed`] if the payload was encrypted with a
+    /// different key, and [`ParseError::InvalidChecksum`] if the checksum of the payload does not
+    /// match.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ruuvi_sensor_protocol::{EncryptionKey, ParseError, SensorValues, Temperature};
+    ///
+    /// let key = EncryptionKey::new([
+    ///     0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88, 0x09, 0xCF, 0x4F,
+    ///     0x3C,
+    /// ]);
+    /// let value = [
+    ///     0x08, 0x4A, 0x16, 0x78, 0x55, 0xDA, 0x58, 0xE7, 0x2C, 0x9A, 0xF7, 0x82, 0x6D, 0xA6, 0x1D,
+    ///     0x87, 0x7D, 0xBC, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    /// ];
+    ///
+    /// let values = SensorValues::from_encrypted_manufacturer_specific_data(0x0499, value, &key)?;
+    /// assert_eq!(values.temperature_as_millicelsius(), Some(24_300));
+    ///
+    /// let wrong_key = EncryptionKey::new([0; 16]);
+    /// let result =
+    ///     SensorValues::from_encrypted_manufacturer_specific_data(0x0499, value, &wrong_key);
+    /// assert_eq!(result, Err(ParseError::DecryptionFailed));
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    #[cfg(feature = "v8")]
+    pub fn from_encrypted_manufacturer_specific_data(
+        id: u16,
+        value: impl AsRef<[u8]>,
+        key: &v8::EncryptionKey,
     ) -> Result<Self, ParseError> {
         match (id, value.as_ref()) {
+            (MANUFACTURER_DATA_ID, [v8::SensorValues::VERSION, data @ ..]) => {
+                let data = data.try_into().map_err(|_| {
+                    ParseError::InvalidValueLength(
+                        v8::SensorValues::VERSION,
+                        data.len() + 1,
+                        v8::SensorValues::SIZE + 1,
+                    )
+                })?;
+
+                let values = Self::from_measurements(&v8::SensorValues::decrypt(data, key)?);
+                Ok(values.with_data_format(Some(DataFormat::V8)))
+            }
+            (id, value) => Self::from_manufacturer_specific_data(id, value),
+        }
+    }
+
+    /// Parses sensor values from a notification sent over Nordic UART Service while connected to
+    /// a tag. The notification contains the same payload as manufacturer specific data, starting
+    /// with the format version, but without the manufacturer id. At the moment only version 5 of
+    /// the format is sent as notifications.
+    ///
+    /// # Errors
+    ///
+    /// The function returns a `ParseError` if the notification is empty, the format version is not
+    /// 5, or the length of the payload is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ruuvi_sensor_protocol::{MeasurementSequenceNumber, SensorValues, Temperature};
+    /// # use ruuvi_sensor_protocol::ParseError;
+    ///
+    /// let notification = [
+    ///     0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+    ///     0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    /// ];
+    /// let values = SensorValues::from_nus_notification(&notification)?;
+    /// assert_eq!(values.temperature_as_millicelsius(), Some(24_300));
+    /// assert_eq!(values.measurement_sequence_number(), Some(205));
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    pub fn from_nus_notification(value: &[u8]) -> Result<Self, ParseError> {
+        match value {
+            [v5::SensorValues::VERSION, data @ ..] => Self::parse_format_version::<
+                v5::SensorValues,
+                { v5::SensorValues::SIZE },
+            >(data, ParseMode::Strict)
+            .map(|(values, _)| values),
+            [version, ..] => Err(ParseError::UnsupportedFormatVersion(*version)),
+            [] => Err(ParseError::EmptyValue),
+        }
+    }
+
+    /// Returns the data format the values were parsed from, if known. Values which were
+    /// not parsed from a Ruuvi data format, such as measurements read from a CSV file, have no
+    /// data format.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ruuvi_sensor_protocol::{DataFormat, SensorValues};
+    /// # use ruuvi_sensor_protocol::ParseError;
+    ///
+    /// let value = [
+    ///     0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+    /// ];
+    /// let values = SensorValues::from_manufacturer_specific_data(0x0499, value)?;
+    /// assert_eq!(values.data_format(), Some(DataFormat::V3));
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    #[must_use]
+    pub fn data_format(&self) -> Option<DataFormat> {
+        self.data_format
+    }
+
+    /// Returns the resolution, range and unit of the field in the data format the values were
+    /// parsed from. Returns `None` if the field is not available, or if the data format is not
+    /// known.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ruuvi_sensor_protocol::{Field, SensorValues, Unit};
+    /// # use ruuvi_sensor_protocol::ParseError;
+    ///
+    /// let value = [
+    ///     0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+    /// ];
+    /// let values = SensorValues::from_manufacturer_specific_data(0x0499, value)?;
+    /// let metadata = values.field_metadata(Field::Temperature);
+    ///
+    /// assert_eq!(metadata.map(|metadata| metadata.resolution), Some(10));
+    /// assert_eq!(metadata.map(|metadata| metadata.unit), Some(Unit::Millikelvin));
+    /// assert_eq!(values.field_metadata(Field::TransmitterPower), None);
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    #[must_use]
+    pub fn field_metadata(&self, field: Field) -> Option<FieldMetadata> {
+        let available = match field {
+            Field::Acceleration => self.acceleration.is_some(),
+            Field::BatteryPotential => self.battery_potential.is_some(),
+            Field::Humidity => self.humidity.is_some(),
+            Field::MacAddress => self.mac_address.is_some(),
+            Field::MeasurementSequenceNumber => self.measurement_sequence_number.is_some(),
+            Field::MovementCounter => self.movement_counter.is_some(),
+            Field::Pressure => self.pressure.is_some(),
+            Field::Temperature => self.temperature.is_some(),
+            Field::TransmitterPower => self.tx_power.is_some(),
+        };
+
+        self.data_format?
+            .field_metadata(field)
+            .filter(|_| available)
+    }
+
+    /// Returns an iterator over the available fields and their values. See [`Fields::new`] for
+    /// iterating over the fields of other types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ruuvi_sensor_protocol::{Field, SensorValues, Value};
+    /// # use ruuvi_sensor_protocol::ParseError;
+    ///
+    /// let value = [
+    ///     0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+    /// ];
+    /// let values = SensorValues::from_manufacturer_specific_data(0x0499, value)?;
+    ///
+    /// for (field, value) in values.fields() {
+    ///     if let Value::Unsigned(value) = value {
+    ///         println!("{field}: {value}");
+    ///     }
+    /// }
+    /// # Ok::<(), ParseError>(())
+    /// ```
+    #[must_use]
+    pub fn fields(&self) -> Fields<'_, Self> {
+        Fields::new(self)
+    }
+
+    pub(crate) fn with_data_format(self, data_format: Option<DataFormat>) -> Self {
+        Self {
+            data_format,
+            ..self
+        }
+    }
+
+    /// Parses sensor values of data format 2 or 4 from the base64 encoded fragment of an
+    /// Eddystone URL `ruu.vi/#<fragment>`.
+    pub(crate) fn from_url_fragment(fragment: &[u8]) -> Result<Self, ParseError> {
+        match url::decode(fragment).ok_or(ParseError::InvalidEncoding)? {
+            [url::V2::VERSION, data @ ..] => Ok(Self::from_measurements(&url::V2::from(&data))
+                .with_data_format(Some(url::V2::FORMAT))),
+            [url::V4::VERSION, data @ ..] => Ok(Self::from_measurements(&url::V4::from(&data))
+                .with_data_format(Some(url::V4::FORMAT))),
+            [version, ..] => Err(ParseError::UnsupportedFormatVersion(version)),
+        }
+    }
+
+    pub(crate) fn parse(
+        id: u16,
+        value: &[u8],
+        mode: ParseMode,
+    ) -> Result<(Self, Option<Leniency>), ParseError> {
+        match (id, value) {
             (MANUFACTURER_DATA_ID, [v3::SensorValues::VERSION, data @ ..]) => {
-                Self::parse_format_version::<v3::SensorValues, { v3::SensorValues::SIZE }>(data)
+                Self::parse_format_version::<v3::SensorValues, { v3::SensorValues::SIZE }>(
+                    data, mode,
+                )
             }
             (MANUFACTURER_DATA_ID, [v5::SensorValues::VERSION, data @ ..]) => {
-                Self::parse_format_version::<v5::SensorValues, { v5::SensorValues::SIZE }>(data)
+                Self::parse_format_version::<v5::SensorValues, { v5::SensorValues::SIZE }>(
+                    data, mode,
+                )
             }
             (MANUFACTURER_DATA_ID, [version, ..]) => {
                 Err(ParseError::UnsupportedFormatVersion(*version))
@@ -76,7 +298,18 @@ impl SensorValues {
         }
     }
 
-    fn from_payload<T: ProtocolPayload>(values: &T) -> Self {
+    pub(crate) fn from_measurements<T>(values: &T) -> Self
+    where
+        T: Acceleration
+            + BatteryPotential
+            + Humidity
+            + MacAddress
+            + MeasurementSequenceNumber
+            + MovementCounter
+            + Pressure
+            + Temperature
+            + TransmitterPower,
+    {
         SensorValues {
             acceleration: values.acceleration_vector_as_milli_g(),
             battery_potential: values.battery_potential_as_millivolts(),
@@ -87,24 +320,38 @@ impl SensorValues {
             pressure: values.pressure_as_pascals(),
             temperature: values.temperature_as_millikelvins(),
             tx_power: values.tx_power_as_dbm(),
+            data_format: None,
         }
     }
 
-    fn parse_format_version<'a, V, const N: usize>(data: &'a [u8]) -> Result<Self, ParseError>
+    fn parse_format_version<V, const N: usize>(
+        data: &[u8],
+        mode: ParseMode,
+    ) -> Result<(Self, Option<Leniency>), ParseError>
     where
-        V: From<&'a [u8; N]> + ProtocolPayload,
+        V: for<'a> From<&'a [u8; N]> + ProtocolPayload,
     {
-        let result: Result<&[u8; N], _> = data.try_into();
-
-        if let Ok(data) = result {
-            let values: &V = &data.into();
-            Ok(SensorValues::from_payload(values))
-        } else {
-            Err(ParseError::InvalidValueLength(
-                V::VERSION,
-                data.len() + 1,
-                N + 1,
-            ))
+        let invalid_length = || ParseError::InvalidValueLength(V::VERSION, data.len() + 1, N + 1);
+
+        match (data.len().cmp(&N), mode) {
+            (Ordering::Equal, _) | (Ordering::Greater, ParseMode::Lenient) => {
+                let (data, trailing) = data.split_at(N);
+                let data: &[u8; N] = data.try_into().map_err(|_| invalid_length())?;
+                let values: &V = &data.into();
+                let leniency = (!trailing.is_empty())
+                    .then_some(Leniency::IgnoredTrailingBytes(trailing.len()));
+
+                let values = SensorValues::from_measurements(values);
+                Ok((values.with_data_format(Some(V::FORMAT)), leniency))
+            }
+            (Ordering::Less, ParseMode::Lenient) => {
+                let values = V::from_truncated(data).ok_or_else(invalid_length)?;
+                let leniency = Leniency::MissingTrailingBytes(N - data.len());
+
+                let values = SensorValues::from_measurements(&values);
+                Ok((values.with_data_format(Some(V::FORMAT)), Some(leniency)))
+            }
+            _ => Err(invalid_length()),
         }
     }
 }
@@ -128,7 +375,7 @@ impl Humidity for SensorValues {
 }
 
 impl MacAddress for SensorValues {
-    fn mac_address(&self) -> Option<[u8; 6]> {
+    fn mac_address(&self) -> Option<Mac> {
         self.mac_address
     }
 }
@@ -244,6 +491,24 @@ mod tests {
                             },
                         }
                     }
+
+                    #[test]
+                    fn data_format() {
+                        assert_eq!(RESULT.data_format().map(DataFormat::version), Some(VERSION));
+                    }
+
+                    #[test]
+                    fn field_metadata() {
+                        for field in Field::ALL {
+                            assert_eq!(
+                                RESULT.field_metadata(field),
+                                RESULT
+                                    .data_format()
+                                    .and_then(|data_format| data_format.field_metadata(field)),
+                                "{field}",
+                            );
+                        }
+                    }
                 }
             )+
         };
@@ -282,6 +547,7 @@ mod tests {
                 pressure: Some(63656),
                 temperature: Some(1690 + 273_150),
                 tx_power: None,
+                data_format: Some(DataFormat::V3),
             },
         }
 
@@ -294,13 +560,150 @@ mod tests {
                 acceleration: Some(AccelerationVector(4, -4, 1036)),
                 battery_potential: Some(2977),
                 humidity: Some(534_900),
-                mac_address: Some([0xcb, 0xb8, 0x33, 0x4c, 0x88, 0x4f]),
+                mac_address: Some(Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F])),
                 measurement_sequence_number: Some(205),
                 movement_counter: Some(66),
                 pressure: Some(100_044),
                 temperature: Some(24_300 + 273_150),
                 tx_power: Some(4),
+                data_format: Some(DataFormat::V5),
             },
         }
     }
+
+    macro_rules! test_nus_notification {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(SensorValues::from_nus_notification(&$input), $result);
+                }
+            )+
+        };
+    }
+
+    const NUS_NOTIFICATION: [u8; 24] = [
+        0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+        0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    ];
+
+    test_nus_notification! {
+        test nus_notification {
+            input: NUS_NOTIFICATION,
+            result: SensorValues::from_manufacturer_specific_data(
+                MANUFACTURER_DATA_ID,
+                NUS_NOTIFICATION,
+            ),
+        }
+
+        test nus_notification_invalid_length {
+            input: NUS_NOTIFICATION[..20],
+            result: Err(ParseError::InvalidValueLength(5, 20, 24)),
+        }
+
+        test nus_notification_trailing_bytes {
+            input: [NUS_NOTIFICATION.as_slice(), &[0x00]].concat(),
+            result: Err(ParseError::InvalidValueLength(5, 25, 24)),
+        }
+
+        test nus_notification_v3 {
+            input: [
+                0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08,
+                0x86,
+            ],
+            result: Err(ParseError::UnsupportedFormatVersion(3)),
+        }
+
+        test nus_notification_empty {
+            input: [],
+            result: Err(ParseError::EmptyValue),
+        }
+    }
+
+    #[cfg(feature = "v8")]
+    mod encrypted {
+        use super::*;
+
+        use crate::formats::v8::testing::{encrypt, KEY, MAC_ADDRESS, PLAINTEXT};
+
+        #[test]
+        fn encrypted_data() {
+            let values = SensorValues::from_encrypted_manufacturer_specific_data(
+                MANUFACTURER_DATA_ID,
+                encrypt(PLAINTEXT, &KEY),
+                &KEY,
+            );
+
+            assert_eq!(
+                values,
+                Ok(SensorValues {
+                    acceleration: None,
+                    battery_potential: Some(2977),
+                    humidity: Some(534_900),
+                    mac_address: Some(Mac::new(MAC_ADDRESS)),
+                    measurement_sequence_number: Some(205),
+                    movement_counter: Some(66),
+                    pressure: Some(100_044),
+                    temperature: Some(24_300 + 273_150),
+                    tx_power: Some(4),
+                    data_format: Some(DataFormat::V8),
+                })
+            );
+        }
+
+        #[test]
+        fn encrypted_data_wrong_key() {
+            let values = SensorValues::from_encrypted_manufacturer_specific_data(
+                MANUFACTURER_DATA_ID,
+                encrypt(PLAINTEXT, &KEY),
+                &v8::EncryptionKey::new([0xFF; 16]),
+            );
+
+            assert_eq!(values, Err(ParseError::DecryptionFailed));
+        }
+
+        #[test]
+        fn encrypted_data_invalid_length() {
+            let values = SensorValues::from_encrypted_manufacturer_specific_data(
+                MANUFACTURER_DATA_ID,
+                &encrypt(PLAINTEXT, &KEY)[..20],
+                &KEY,
+            );
+
+            assert_eq!(values, Err(ParseError::InvalidValueLength(8, 20, 24)));
+        }
+
+        #[test]
+        fn encrypted_data_without_key() {
+            let values = SensorValues::from_manufacturer_specific_data(
+                MANUFACTURER_DATA_ID,
+                encrypt(PLAINTEXT, &KEY),
+            );
+
+            assert_eq!(values, Err(ParseError::UnsupportedFormatVersion(8)));
+        }
+
+        #[test]
+        fn unencrypted_data_with_key() {
+            let value = [
+                0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+            ];
+
+            assert_eq!(
+                SensorValues::from_encrypted_manufacturer_specific_data(
+                    MANUFACTURER_DATA_ID,
+                    value,
+                    &KEY
+                ),
+                SensorValues::from_manufacturer_specific_data(MANUFACTURER_DATA_ID, value)
+            );
+        }
+    }
 }
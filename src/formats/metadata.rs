@@ -0,0 +1,289 @@
+use crate::formats::{DataFormat, Field};
+
+/// Unit of the values returned by the measurement traits
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Unit {
+    /// Milli-kelvins, see [`Temperature`](crate::Temperature)
+    Millikelvin,
+    /// Parts per million of relative humidity, see [`Humidity`](crate::Humidity)
+    PartsPerMillion,
+    /// Pascals, see [`Pressure`](crate::Pressure)
+    Pascal,
+    /// Milli-G of each acceleration component, see [`Acceleration`](crate::Acceleration)
+    MilliG,
+    /// Milli-volts, see [`BatteryPotential`](crate::BatteryPotential)
+    Millivolt,
+    /// Decibel-milliwatts, see [`TransmitterPower`](crate::TransmitterPower)
+    Dbm,
+    /// Dimensionless count, used for counters and sequence numbers
+    Count,
+}
+
+impl Unit {
+    /// Returns the symbol of the unit, which is empty for counts
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Unit::Millikelvin => "mK",
+            Unit::PartsPerMillion => "ppm",
+            Unit::Pascal => "Pa",
+            Unit::MilliG => "mG",
+            Unit::Millivolt => "mV",
+            Unit::Dbm => "dBm",
+            Unit::Count => "",
+        }
+    }
+}
+
+/// Resolution, range and unit of a field in a data format
+///
+/// All values are in the unit of the corresponding measurement trait, for example temperature
+/// resolution is in milli-kelvins even though the trait also offers milli-Celsius.
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::{DataFormat, Field, Unit};
+///
+/// let v3 = DataFormat::V3.field_metadata(Field::Humidity);
+/// let v5 = DataFormat::V5.field_metadata(Field::Humidity);
+///
+/// assert_eq!(v3.map(|metadata| metadata.resolution), Some(5_000));
+/// assert_eq!(v5.map(|metadata| metadata.resolution), Some(25));
+/// assert_eq!(v5.map(|metadata| metadata.unit), Some(Unit::PartsPerMillion));
+/// assert_eq!(DataFormat::V3.field_metadata(Field::MovementCounter), None);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FieldMetadata {
+    /// Smallest difference between two values the format can represent
+    pub resolution: u32,
+    /// Smallest value the format can represent
+    pub minimum: i64,
+    /// Largest value the format can represent
+    pub maximum: i64,
+    /// Unit of the resolution and the range
+    pub unit: Unit,
+}
+
+impl FieldMetadata {
+    const fn new(resolution: u32, minimum: i64, maximum: i64, unit: Unit) -> Self {
+        Self {
+            resolution,
+            minimum,
+            maximum,
+            unit,
+        }
+    }
+}
+
+/// Temperature encoded as a sign bit, 7-bit integer part and a fraction byte in 0.01 °C. The
+/// fraction byte is not limited to 99, so the magnitude goes up to 127 °C + 2.55 °C.
+const SIGN_AND_FRACTION_TEMPERATURE: FieldMetadata =
+    FieldMetadata::new(10, 273_150 - 129_550, 273_150 + 129_550, Unit::Millikelvin);
+/// Humidity encoded in 0.5 % steps in one byte
+const HALF_PERCENT_HUMIDITY: FieldMetadata =
+    FieldMetadata::new(5_000, 0, 255 * 5_000, Unit::PartsPerMillion);
+/// Temperature encoded as a signed 16-bit integer in 0.005 °C steps
+const SIGNED_TEMPERATURE: FieldMetadata =
+    FieldMetadata::new(5, 273_150 - 163_835, 273_150 + 163_835, Unit::Millikelvin);
+/// Humidity encoded as an unsigned 16-bit integer in 0.0025 % steps
+const WORD_HUMIDITY: FieldMetadata = FieldMetadata::new(25, 0, 0xFFFE * 25, Unit::PartsPerMillion);
+
+impl DataFormat {
+    /// Returns the resolution, range and unit of the field in the data format, or `None` if the
+    /// data format does not contain the field. MAC address has no metadata.
+    #[must_use]
+    pub const fn field_metadata(self, field: Field) -> Option<FieldMetadata> {
+        let metadata = match (self, field) {
+            (DataFormat::V2 | DataFormat::V3 | DataFormat::V4, Field::Temperature) => {
+                SIGN_AND_FRACTION_TEMPERATURE
+            }
+            (DataFormat::V2 | DataFormat::V3 | DataFormat::V4, Field::Humidity) => {
+                HALF_PERCENT_HUMIDITY
+            }
+            (DataFormat::V2 | DataFormat::V3 | DataFormat::V4, Field::Pressure) => {
+                FieldMetadata::new(1, 50_000, 50_000 + 0xFFFF, Unit::Pascal)
+            }
+            (DataFormat::V3, Field::Acceleration) => {
+                FieldMetadata::new(1, -0x8000, 0x7FFF, Unit::MilliG)
+            }
+            (DataFormat::V3, Field::BatteryPotential) => {
+                FieldMetadata::new(1, 0, 0xFFFF, Unit::Millivolt)
+            }
+            (DataFormat::V5 | DataFormat::V8, Field::Temperature) => SIGNED_TEMPERATURE,
+            (DataFormat::V5 | DataFormat::V8, Field::Humidity) => WORD_HUMIDITY,
+            (DataFormat::V5 | DataFormat::V8, Field::Pressure) => {
+                FieldMetadata::new(1, 50_000, 50_000 + 0xFFFE, Unit::Pascal)
+            }
+            (DataFormat::V5, Field::Acceleration) => {
+                FieldMetadata::new(1, -0x7FFF, 0x7FFF, Unit::MilliG)
+            }
+            (DataFormat::V5 | DataFormat::V8, Field::BatteryPotential) => {
+                FieldMetadata::new(1, 1_600, 1_600 + 2_046, Unit::Millivolt)
+            }
+            (DataFormat::V5 | DataFormat::V8, Field::TransmitterPower) => {
+                FieldMetadata::new(2, -40, 20, Unit::Dbm)
+            }
+            (DataFormat::V5 | DataFormat::V8, Field::MovementCounter) => {
+                FieldMetadata::new(1, 0, 0xFE, Unit::Count)
+            }
+            (DataFormat::V5 | DataFormat::V8, Field::MeasurementSequenceNumber) => {
+                FieldMetadata::new(1, 0, 0xFFFE, Unit::Count)
+            }
+            _ => return None,
+        };
+
+        Some(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Temperature;
+
+    #[test]
+    fn field_metadata_has_default_traits() {
+        crate::testing::type_has_default_traits::<FieldMetadata>();
+    }
+
+    #[test]
+    fn unit_has_default_traits() {
+        crate::testing::type_has_default_traits::<Unit>();
+    }
+
+    macro_rules! test_field_metadata {
+        (
+            $(
+                test $name: ident {
+                    input: ($data_format: expr, $field: expr),
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!($data_format.field_metadata($field), $result);
+                }
+            )+
+        };
+    }
+
+    test_field_metadata! {
+        test v3_temperature {
+            input: (DataFormat::V3, Field::Temperature),
+            result: Some(FieldMetadata::new(10, 143_600, 402_700, Unit::Millikelvin)),
+        }
+
+        test v3_humidity {
+            input: (DataFormat::V3, Field::Humidity),
+            result: Some(FieldMetadata::new(5_000, 0, 1_275_000, Unit::PartsPerMillion)),
+        }
+
+        test v3_pressure {
+            input: (DataFormat::V3, Field::Pressure),
+            result: Some(FieldMetadata::new(1, 50_000, 115_535, Unit::Pascal)),
+        }
+
+        test v3_acceleration {
+            input: (DataFormat::V3, Field::Acceleration),
+            result: Some(FieldMetadata::new(1, -0x8000, 0x7FFF, Unit::MilliG)),
+        }
+
+        test v3_tx_power {
+            input: (DataFormat::V3, Field::TransmitterPower),
+            result: None,
+        }
+
+        test v4_humidity {
+            input: (DataFormat::V4, Field::Humidity),
+            result: DataFormat::V3.field_metadata(Field::Humidity),
+        }
+
+        test v4_acceleration {
+            input: (DataFormat::V4, Field::Acceleration),
+            result: None,
+        }
+
+        test v5_temperature {
+            input: (DataFormat::V5, Field::Temperature),
+            result: Some(FieldMetadata::new(5, 109_315, 436_985, Unit::Millikelvin)),
+        }
+
+        test v5_humidity {
+            input: (DataFormat::V5, Field::Humidity),
+            result: Some(FieldMetadata::new(25, 0, 1_638_350, Unit::PartsPerMillion)),
+        }
+
+        test v5_battery_potential {
+            input: (DataFormat::V5, Field::BatteryPotential),
+            result: Some(FieldMetadata::new(1, 1_600, 3_646, Unit::Millivolt)),
+        }
+
+        test v5_tx_power {
+            input: (DataFormat::V5, Field::TransmitterPower),
+            result: Some(FieldMetadata::new(2, -40, 20, Unit::Dbm)),
+        }
+
+        test v5_mac_address {
+            input: (DataFormat::V5, Field::MacAddress),
+            result: None,
+        }
+
+        test v8_acceleration {
+            input: (DataFormat::V8, Field::Acceleration),
+            result: None,
+        }
+
+        test v8_measurement_sequence_number {
+            input: (DataFormat::V8, Field::MeasurementSequenceNumber),
+            result: Some(FieldMetadata::new(1, 0, 0xFFFE, Unit::Count)),
+        }
+    }
+
+    #[test]
+    fn v3_temperature_extremes_are_in_range() {
+        let metadata = DataFormat::V3.field_metadata(Field::Temperature);
+        let temperature = |bytes: [u8; 2]| {
+            let [temperature_1, temperature_2] = bytes;
+            let value = [
+                3,
+                0,
+                temperature_1,
+                temperature_2,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+            ];
+            crate::SensorValues::from_manufacturer_specific_data(0x0499, value)
+                .ok()
+                .and_then(|values| values.temperature_as_millikelvins())
+                .map(i64::from)
+        };
+
+        assert_eq!(
+            temperature([0x7F, 0xFF]),
+            metadata.map(|metadata| metadata.maximum)
+        );
+        assert_eq!(
+            temperature([0xFF, 0xFF]),
+            metadata.map(|metadata| metadata.minimum)
+        );
+    }
+
+    #[test]
+    fn unit_symbols() {
+        assert_eq!(Unit::Millikelvin.symbol(), "mK");
+        assert_eq!(Unit::Count.symbol(), "");
+    }
+}
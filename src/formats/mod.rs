@@ -1,5 +1,11 @@
+#[cfg(feature = "v8")]
+pub use crate::formats::v8::EncryptionKey;
 pub use crate::formats::{
+    data_format::DataFormat,
+    field::{Field, Fields, Value},
     generic::SensorValues,
+    metadata::{FieldMetadata, Unit},
+    parser::{Leniency, ParseMode, Parsed, Parser},
     traits::{
         Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
         MovementCounter, Pressure, Temperature, TransmitterPower,
@@ -10,13 +16,20 @@ pub use crate::formats::{
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct AccelerationVector(pub i16, pub i16, pub i16);
 
+mod data_format;
+mod field;
 mod generic;
+mod metadata;
+mod parser;
 mod traits;
+mod url;
 mod v3;
 mod v5;
+#[cfg(feature = "v8")]
+mod v8;
 
 #[cfg(test)]
-mod testing;
+pub(crate) mod testing;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,235 @@
+use crate::{errors::ParseError, formats::generic::SensorValues};
+
+/// Selects how strictly the length of a payload is checked during parsing
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ParseMode {
+    /// Length of the payload must match the length of the data format exactly
+    #[default]
+    Strict,
+    /// Trailing bytes after the payload are ignored, and fields missing from the end of a
+    /// truncated payload are treated as unavailable
+    Lenient,
+}
+
+/// A deviation from the data format specification which was accepted in lenient mode
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Leniency {
+    /// The payload was longer than the data format, and the given number of trailing bytes were
+    /// ignored
+    IgnoredTrailingBytes(usize),
+    /// The payload was shorter than the data format by the given number of bytes, and the
+    /// missing fields were treated as unavailable
+    MissingTrailingBytes(usize),
+}
+
+/// Parser for manufacturer specific data with configurable strictness
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::{Leniency, MacAddress, Parser, Temperature};
+/// # use ruuvi_sensor_protocol::ParseError;
+///
+/// let id = 0x0499;
+/// let value = [
+///     0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+///     0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33,
+/// ];
+///
+/// assert!(Parser::strict().parse_manufacturer_specific_data(id, value).is_err());
+///
+/// let parsed = Parser::lenient().parse_manufacturer_specific_data(id, value)?;
+/// assert_eq!(parsed.values.temperature_as_millicelsius(), Some(24_300));
+/// assert_eq!(parsed.values.mac_address(), None);
+/// assert_eq!(parsed.leniency, Some(Leniency::MissingTrailingBytes(3)));
+/// # Ok::<(), ParseError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Parser {
+    mode: ParseMode,
+}
+
+/// Result of a successful parse with a [`Parser`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parsed {
+    /// Values parsed from the payload
+    pub values: SensorValues,
+    /// Deviation from the data format which was accepted during parsing, if any
+    pub leniency: Option<Leniency>,
+}
+
+impl Parser {
+    /// Returns a parser which only accepts payloads that match the data format exactly
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self::new(ParseMode::Strict)
+    }
+
+    /// Returns a parser which accepts trailing bytes and truncated payloads
+    #[must_use]
+    pub const fn lenient() -> Self {
+        Self::new(ParseMode::Lenient)
+    }
+
+    /// Returns a parser with the given mode
+    #[must_use]
+    pub const fn new(mode: ParseMode) -> Self {
+        Self { mode }
+    }
+
+    /// Returns the mode of the parser
+    #[must_use]
+    pub const fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    /// Parses sensor values from the payload encoded in manufacturer specific data -field.
+    ///
+    /// # Errors
+    ///
+    /// The function returns a `ParseError` if the `id` does not match the expected `id` in the
+    /// manufacturer specific data, the format of the `value` is not supported, or the length of
+    /// the `value` is not accepted in the mode of the parser.
+    pub fn parse_manufacturer_specific_data(
+        &self,
+        id: u16,
+        value: impl AsRef<[u8]>,
+    ) -> Result<Parsed, ParseError> {
+        let (values, leniency) = SensorValues::parse(id, value.as_ref(), self.mode)?;
+        Ok(Parsed { values, leniency })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{MacAddress, Temperature};
+
+    const V3_INPUT: [u8; 14] = [
+        0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+    ];
+    const V5_INPUT: [u8; 24] = [
+        0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+        0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    ];
+    const V5_PADDED_INPUT: [u8; 26] = [
+        0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+        0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn parser_has_default_traits() {
+        crate::testing::type_has_default_traits::<Parser>();
+        crate::testing::type_has_default_traits::<Parsed>();
+    }
+
+    #[test]
+    fn default_parser_is_strict() {
+        assert_eq!(Parser::default(), Parser::strict());
+        assert_eq!(Parser::default().mode(), ParseMode::Strict);
+    }
+
+    macro_rules! test_parser {
+        (
+            $(
+                test $name: ident {
+                    parser: $parser: expr,
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = $parser
+                        .parse_manufacturer_specific_data(0x0499, $input)
+                        .map(|parsed| parsed.leniency);
+                    assert_eq!(result, $result);
+                }
+            )+
+        };
+    }
+
+    test_parser! {
+        test strict_exact_length {
+            parser: Parser::strict(),
+            input: V5_INPUT,
+            result: Ok(None),
+        }
+
+        test strict_trailing_bytes {
+            parser: Parser::strict(),
+            input: V5_PADDED_INPUT,
+            result: Err(ParseError::InvalidValueLength(5, 26, 24)),
+        }
+
+        test strict_truncated {
+            parser: Parser::strict(),
+            input: &V5_INPUT[..21],
+            result: Err(ParseError::InvalidValueLength(5, 21, 24)),
+        }
+
+        test lenient_exact_length {
+            parser: Parser::lenient(),
+            input: V5_INPUT,
+            result: Ok(None),
+        }
+
+        test lenient_trailing_bytes {
+            parser: Parser::lenient(),
+            input: V5_PADDED_INPUT,
+            result: Ok(Some(Leniency::IgnoredTrailingBytes(2))),
+        }
+
+        test lenient_truncated {
+            parser: Parser::lenient(),
+            input: &V5_INPUT[..21],
+            result: Ok(Some(Leniency::MissingTrailingBytes(3))),
+        }
+
+        test lenient_truncated_without_fields {
+            parser: Parser::lenient(),
+            input: &V5_INPUT[..2],
+            result: Err(ParseError::InvalidValueLength(5, 2, 24)),
+        }
+
+        test lenient_truncated_v3 {
+            parser: Parser::lenient(),
+            input: &V3_INPUT[..10],
+            result: Err(ParseError::InvalidValueLength(3, 10, 14)),
+        }
+
+        test lenient_unsupported_format {
+            parser: Parser::lenient(),
+            input: [0, 1, 2, 3],
+            result: Err(ParseError::UnsupportedFormatVersion(0)),
+        }
+    }
+
+    #[test]
+    fn lenient_trailing_bytes_values() {
+        let padded = Parser::lenient().parse_manufacturer_specific_data(0x0499, V5_PADDED_INPUT);
+        let exact = Parser::strict().parse_manufacturer_specific_data(0x0499, V5_INPUT);
+        assert_eq!(
+            padded.map(|parsed| parsed.values),
+            exact.map(|parsed| parsed.values)
+        );
+    }
+
+    #[test]
+    fn lenient_truncated_values() {
+        let result = Parser::lenient().parse_manufacturer_specific_data(0x0499, &V5_INPUT[..21]);
+        let values = result.map(|parsed| parsed.values);
+        assert_eq!(
+            values
+                .as_ref()
+                .map(Temperature::temperature_as_millicelsius),
+            Ok(Some(24_300))
+        );
+        assert_eq!(values.as_ref().map(MacAddress::mac_address), Ok(None));
+    }
+}
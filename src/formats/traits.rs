@@ -1,4 +1,7 @@
-use crate::formats::AccelerationVector;
+use crate::{
+    formats::{AccelerationVector, DataFormat},
+    mac::Mac,
+};
 
 pub trait Acceleration {
     /// Returns a three-dimensional acceleration vector where each component is in milli-G if an
@@ -18,7 +21,7 @@ pub trait Humidity {
 
 pub trait MacAddress {
     /// Returns the MAC address of the sensor if available.
-    fn mac_address(&self) -> Option<[u8; 6]>;
+    fn mac_address(&self) -> Option<Mac>;
 }
 
 pub trait MeasurementSequenceNumber {
@@ -67,8 +70,19 @@ pub trait ProtocolPayload:
     + Temperature
     + TransmitterPower
 {
+    const FORMAT: DataFormat;
     const VERSION: u8;
     const SIZE: usize;
+
+    /// Parses a payload which is shorter than `SIZE` by treating the missing trailing fields as
+    /// unavailable. Returns `None` if the format has no way to represent unavailable fields or if
+    /// the payload does not contain any complete field.
+    fn from_truncated(_data: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 #[cfg(test)]
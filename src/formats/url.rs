@@ -0,0 +1,223 @@
+use crate::{
+    formats::{
+        traits::{
+            Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
+            MovementCounter, Pressure, ProtocolPayload, Temperature, TransmitterPower,
+        },
+        AccelerationVector, DataFormat,
+    },
+    mac::Mac,
+};
+
+/// Raw sensor values of data formats 2 and 4, which are broadcast as an Eddystone URL
+/// `ruu.vi/#<payload>` where the payload is encoded with base64.
+///
+/// Both formats contain humidity, temperature and pressure in the same encoding as data format 3.
+/// Data format 4 adds an extra character for a tag id to the end of the URL, which is ignored.
+#[derive(Debug, Eq, PartialEq)]
+pub struct SensorValues<const VERSION: u8> {
+    humidity: u8,
+    temperature: u16,
+    pressure: u16,
+}
+
+pub type V2 = SensorValues<2>;
+pub type V4 = SensorValues<4>;
+
+/// Number of base64 characters encoding the format version and the payload
+pub const ENCODED_SIZE: usize = 8;
+
+/// Decodes the format version and the payload from the beginning of the URL fragment. Both the
+/// standard and the URL-safe alphabets are accepted, since the tags have used both.
+pub fn decode(encoded: &[u8]) -> Option<[u8; 6]> {
+    let encoded: &[u8; ENCODED_SIZE] = encoded.get(..ENCODED_SIZE)?.try_into().ok()?;
+    let mut decoded = [0; 6];
+
+    for (chunk, decoded) in encoded.chunks_exact(4).zip(decoded.chunks_exact_mut(3)) {
+        let bits = chunk.iter().try_fold(0_u32, |bits, character| {
+            Some(bits << 6 | u32::from(base64_value(*character)?))
+        })?;
+        decoded.copy_from_slice(&bits.to_be_bytes()[1..]);
+    }
+
+    Some(decoded)
+}
+
+fn base64_value(character: u8) -> Option<u8> {
+    match character {
+        b'A'..=b'Z' => Some(character - b'A'),
+        b'a'..=b'z' => Some(character - b'a' + 26),
+        b'0'..=b'9' => Some(character - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+impl<const VERSION: u8> Acceleration for SensorValues<VERSION> {
+    fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
+        None
+    }
+}
+
+impl<const VERSION: u8> BatteryPotential for SensorValues<VERSION> {
+    fn battery_potential_as_millivolts(&self) -> Option<u16> {
+        None
+    }
+}
+
+impl<const VERSION: u8> Humidity for SensorValues<VERSION> {
+    fn humidity_as_ppm(&self) -> Option<u32> {
+        Some(u32::from(self.humidity) * 5_000)
+    }
+}
+
+impl<const VERSION: u8> MacAddress for SensorValues<VERSION> {
+    fn mac_address(&self) -> Option<Mac> {
+        None
+    }
+}
+
+impl<const VERSION: u8> MeasurementSequenceNumber for SensorValues<VERSION> {
+    fn measurement_sequence_number(&self) -> Option<u32> {
+        None
+    }
+}
+
+impl<const VERSION: u8> MovementCounter for SensorValues<VERSION> {
+    fn movement_counter(&self) -> Option<u32> {
+        None
+    }
+}
+
+impl<const VERSION: u8> Pressure for SensorValues<VERSION> {
+    fn pressure_as_pascals(&self) -> Option<u32> {
+        Some(u32::from(self.pressure) + 50_000)
+    }
+}
+
+impl<const VERSION: u8> Temperature for SensorValues<VERSION> {
+    fn temperature_as_millikelvins(&self) -> Option<u32> {
+        let integer_part = u32::from((self.temperature >> 8) & 0x7F);
+        let decimal_part = u32::from(self.temperature & 0xFF);
+        let absolute_value = integer_part * 1000 + decimal_part * 10;
+
+        let temperature = if self.temperature >> 15 == 0 {
+            Self::ZERO_CELSIUS_IN_MILLIKELVINS + absolute_value
+        } else {
+            Self::ZERO_CELSIUS_IN_MILLIKELVINS - absolute_value
+        };
+
+        Some(temperature)
+    }
+}
+
+impl<const VERSION: u8> TransmitterPower for SensorValues<VERSION> {
+    fn tx_power_as_dbm(&self) -> Option<i8> {
+        None
+    }
+}
+
+impl ProtocolPayload for SensorValues<2> {
+    const FORMAT: DataFormat = DataFormat::V2;
+    const VERSION: u8 = 2;
+    const SIZE: usize = 5;
+}
+
+impl ProtocolPayload for SensorValues<4> {
+    const FORMAT: DataFormat = DataFormat::V4;
+    const VERSION: u8 = 4;
+    const SIZE: usize = 5;
+}
+
+impl<const VERSION: u8> From<&[u8; 5]> for SensorValues<VERSION> {
+    fn from(value: &[u8; 5]) -> Self {
+        let [humidity, temperature_1, temperature_2, pressure_1, pressure_2] = value;
+        Self {
+            humidity: *humidity,
+            temperature: u16::from_be_bytes([*temperature_1, *temperature_2]),
+            pressure: u16::from_be_bytes([*pressure_1, *pressure_2]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::formats::testing::test_measurement_trait_methods;
+
+    macro_rules! test_decode {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(decode($input), $result);
+                }
+            )+
+        };
+    }
+
+    test_decode! {
+        test decode_standard_alphabet {
+            input: b"BEAYAMFs",
+            result: Some([0x04, 0x40, 0x18, 0x00, 0xC1, 0x6C]),
+        }
+
+        test decode_url_safe_alphabet {
+            input: b"AjR-_w-_",
+            result: Some([0x02, 0x34, 0x7E, 0xFF, 0x0F, 0xBF]),
+        }
+
+        test decode_plus_and_slash {
+            input: b"AjR+/w+/",
+            result: Some([0x02, 0x34, 0x7E, 0xFF, 0x0F, 0xBF]),
+        }
+
+        test decode_ignores_trailing_characters {
+            input: b"BEAYAMFsA",
+            result: Some([0x04, 0x40, 0x18, 0x00, 0xC1, 0x6C]),
+        }
+
+        test decode_too_short {
+            input: b"BEAYAMF",
+            result: None,
+        }
+
+        test decode_invalid_character {
+            input: b"BEAYAM=s",
+            result: None,
+        }
+    }
+
+    test_measurement_trait_methods! {
+        test positive_inputs {
+            values: V4::from(&[0x40, 0x18, 0x00, 0xC1, 0x6C]),
+            expected: {
+                acceleration_vector_as_milli_g: None,
+                battery_potential_as_millivolts: None,
+                humidity_as_ppm: Some(320_000),
+                mac_address: None,
+                measurement_sequence_number: None,
+                movement_counter: None,
+                pressure_as_pascals: Some(99_516),
+                temperature_as_millicelsius: Some(24_000),
+                tx_power_as_dbm: None,
+            },
+        }
+
+        test negative_inputs {
+            values: V2::from(&[0x40, 0x85, 0x32, 0xC1, 0x6C]),
+            expected: {
+                temperature_as_millicelsius: Some(-5_500),
+            },
+        }
+    }
+}
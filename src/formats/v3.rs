@@ -1,9 +1,12 @@
-use crate::formats::{
-    traits::{
-        Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
-        MovementCounter, Pressure, ProtocolPayload, Temperature, TransmitterPower,
+use crate::{
+    formats::{
+        traits::{
+            Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
+            MovementCounter, Pressure, ProtocolPayload, Temperature, TransmitterPower,
+        },
+        AccelerationVector, DataFormat,
     },
-    AccelerationVector,
+    mac::Mac,
 };
 
 #[derive(Debug, Eq, PartialEq)]
@@ -34,7 +37,7 @@ impl Humidity for SensorValues {
 }
 
 impl MacAddress for SensorValues {
-    fn mac_address(&self) -> Option<[u8; 6]> {
+    fn mac_address(&self) -> Option<Mac> {
         None
     }
 }
@@ -80,6 +83,7 @@ impl TransmitterPower for SensorValues {
 }
 
 impl ProtocolPayload for SensorValues {
+    const FORMAT: DataFormat = DataFormat::V3;
     const VERSION: u8 = 3;
     const SIZE: usize = 13;
 }
@@ -1,9 +1,12 @@
-use crate::formats::{
-    traits::{
-        Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
-        MovementCounter, Pressure, ProtocolPayload, Temperature, TransmitterPower,
+use crate::{
+    formats::{
+        traits::{
+            Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
+            MovementCounter, Pressure, ProtocolPayload, Temperature, TransmitterPower,
+        },
+        AccelerationVector, DataFormat,
     },
-    AccelerationVector,
+    mac::Mac,
 };
 
 /// Raw sensor values parsed from manufacturer data.
@@ -21,7 +24,7 @@ pub struct SensorValues {
 
 impl Acceleration for SensorValues {
     fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
-        if self.acceleration.iter().any(|acc| *acc == i16::MIN) {
+        if self.acceleration.contains(&i16::MIN) {
             None
         } else {
             Some(AccelerationVector(
@@ -56,11 +59,11 @@ impl Humidity for SensorValues {
 }
 
 impl MacAddress for SensorValues {
-    fn mac_address(&self) -> Option<[u8; 6]> {
+    fn mac_address(&self) -> Option<Mac> {
         if self.mac_address == [0xFF; 6] {
             None
         } else {
-            Some(self.mac_address)
+            Some(Mac::new(self.mac_address))
         }
     }
 }
@@ -127,9 +130,34 @@ impl TransmitterPower for SensorValues {
     }
 }
 
+impl SensorValues {
+    /// Offsets where each field of the payload ends, used for dropping partial trailing fields
+    const FIELD_ENDS: [usize; 10] = [2, 4, 6, 8, 10, 12, 14, 15, 17, 23];
+
+    /// Payload where every field has its "not available" value
+    const NOT_AVAILABLE: [u8; Self::SIZE] = [
+        0x80, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 0x80, 0x00, 0x80, 0x00, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    ];
+}
+
 impl ProtocolPayload for SensorValues {
+    const FORMAT: DataFormat = DataFormat::V5;
     const VERSION: u8 = 5;
     const SIZE: usize = 23;
+
+    fn from_truncated(data: &[u8]) -> Option<Self> {
+        let end = Self::FIELD_ENDS
+            .iter()
+            .copied()
+            .take_while(|end| *end <= data.len())
+            .last()?;
+
+        let mut value = Self::NOT_AVAILABLE;
+        value[..end].copy_from_slice(&data[..end]);
+
+        Some(Self::from(&value))
+    }
 }
 
 impl From<&[u8; Self::SIZE]> for SensorValues {
@@ -157,6 +185,7 @@ impl From<&[u8; Self::SIZE]> for SensorValues {
     }
 }
 
+#[expect(clippy::unwrap_used)]
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +211,19 @@ mod tests {
         0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
     ];
 
+    #[test]
+    fn not_available_values() {
+        assert_eq!(
+            SensorValues::from(&SensorValues::NOT_AVAILABLE),
+            SensorValues::from(&INVALID_VALUES)
+        );
+    }
+
+    #[test]
+    fn truncated_without_complete_fields() {
+        assert_eq!(SensorValues::from_truncated(&VALID_VALUES[..1]), None);
+    }
+
     #[test]
     fn valid_input() {
         assert_eq!(
@@ -206,7 +248,7 @@ mod tests {
                 acceleration_vector_as_milli_g: Some(AccelerationVector(4, -4, 1_036)),
                 battery_potential_as_millivolts: Some(2_977),
                 humidity_as_ppm: Some(534_900),
-                mac_address: Some([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F]),
+                mac_address: Some(Mac::new([0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F])),
                 measurement_sequence_number: Some(205),
                 movement_counter: Some(66),
                 pressure_as_pascals: Some(100_044),
@@ -215,6 +257,28 @@ mod tests {
             },
         }
 
+        test truncated_mac_address {
+            values: SensorValues::from_truncated(&VALID_VALUES[..20]).unwrap(),
+            expected: {
+                mac_address: None,
+                measurement_sequence_number: Some(205),
+                movement_counter: Some(66),
+                temperature_as_millicelsius: Some(24_300),
+            },
+        }
+
+        test truncated_power_info {
+            values: SensorValues::from_truncated(&VALID_VALUES[..13]).unwrap(),
+            expected: {
+                acceleration_vector_as_milli_g: Some(AccelerationVector(4, -4, 1_036)),
+                battery_potential_as_millivolts: None,
+                mac_address: None,
+                measurement_sequence_number: None,
+                movement_counter: None,
+                tx_power_as_dbm: None,
+            },
+        }
+
         test invalid_values {
             values: SensorValues::from(&INVALID_VALUES),
             expected: {
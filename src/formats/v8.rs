@@ -0,0 +1,338 @@
+use aes::{
+    cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit},
+    Aes128,
+};
+
+use crate::{
+    errors::ParseError,
+    formats::{
+        traits::{
+            Acceleration, BatteryPotential, Humidity, MacAddress, MeasurementSequenceNumber,
+            MovementCounter, Pressure, Temperature, TransmitterPower,
+        },
+        AccelerationVector,
+    },
+    mac::Mac,
+};
+
+/// 128-bit AES key used by a tag for encrypting data format 8 payloads.
+///
+/// Deriving the key from a tag password is not supported, the key has to be given as bytes.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct EncryptionKey([u8; 16]);
+
+impl EncryptionKey {
+    /// Returns a key from its bytes
+    #[must_use]
+    pub const fn new(key: [u8; 16]) -> Self {
+        Self(key)
+    }
+}
+
+impl From<[u8; 16]> for EncryptionKey {
+    fn from(key: [u8; 16]) -> Self {
+        Self(key)
+    }
+}
+
+impl core::fmt::Debug for EncryptionKey {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        formatter.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Raw sensor values decrypted from manufacturer data.
+///
+/// The payload contains a 16 byte block encrypted with AES-128 in ECB mode, a CRC8 checksum of the
+/// decrypted block and the MAC address of the tag. The decrypted block contains temperature,
+/// humidity, pressure, power info, movement counter and measurement sequence number in the same
+/// encoding as data format 5, followed by reserved bytes which are zero.
+#[derive(Debug, Eq, PartialEq)]
+pub struct SensorValues {
+    humidity: u16,
+    temperature: i16,
+    pressure: u16,
+    power_info: u16,
+    movement_counter: u8,
+    measurement_sequence_number: u16,
+    mac_address: [u8; 6],
+}
+
+/// Offset of the reserved bytes in the decrypted block, which are zero
+const RESERVED: usize = 11;
+
+impl SensorValues {
+    pub const VERSION: u8 = 8;
+    pub const SIZE: usize = 23;
+
+    /// Decrypts and checks the payload without the format version.
+    ///
+    /// When the checksum does not match, the reserved bytes of the decrypted block tell a wrong
+    /// key apart from a corrupted checksum: the reserved bytes are zero, but decrypting with a
+    /// wrong key scrambles the whole block.
+    pub fn decrypt(value: &[u8; Self::SIZE], key: &EncryptionKey) -> Result<Self, ParseError> {
+        let mut block = GenericArray::clone_from_slice(&value[..16]);
+        Aes128::new(&GenericArray::from(key.0)).decrypt_block(&mut block);
+        let block: [u8; 16] = block.into();
+        let checksum = value[16];
+
+        if crc8(&block) != checksum {
+            return if block[RESERVED..].iter().all(|byte| *byte == 0) {
+                Err(ParseError::InvalidChecksum(checksum, crc8(&block)))
+            } else {
+                Err(ParseError::DecryptionFailed)
+            };
+        }
+
+        let mut mac_address = [0; 6];
+        mac_address.copy_from_slice(&value[17..]);
+
+        Ok(Self {
+            temperature: i16::from_be_bytes([block[0], block[1]]),
+            humidity: u16::from_be_bytes([block[2], block[3]]),
+            pressure: u16::from_be_bytes([block[4], block[5]]),
+            power_info: u16::from_be_bytes([block[6], block[7]]),
+            movement_counter: block[8],
+            measurement_sequence_number: u16::from_be_bytes([block[9], block[10]]),
+            mac_address,
+        })
+    }
+}
+
+/// CRC-8 with polynomial 0x07 and zero initial value
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x07
+            }
+        })
+    })
+}
+
+impl Acceleration for SensorValues {
+    fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
+        None
+    }
+}
+
+impl BatteryPotential for SensorValues {
+    fn battery_potential_as_millivolts(&self) -> Option<u16> {
+        let raw_value = self.power_info >> 5;
+
+        if raw_value == 2047 {
+            None
+        } else {
+            Some(1_600 + raw_value)
+        }
+    }
+}
+
+impl Humidity for SensorValues {
+    fn humidity_as_ppm(&self) -> Option<u32> {
+        if self.humidity == 0xFFFF {
+            None
+        } else {
+            Some(u32::from(self.humidity) * 25)
+        }
+    }
+}
+
+impl MacAddress for SensorValues {
+    fn mac_address(&self) -> Option<Mac> {
+        if self.mac_address == [0xFF; 6] {
+            None
+        } else {
+            Some(Mac::new(self.mac_address))
+        }
+    }
+}
+
+impl MeasurementSequenceNumber for SensorValues {
+    fn measurement_sequence_number(&self) -> Option<u32> {
+        if self.measurement_sequence_number == 0xFFFF {
+            None
+        } else {
+            Some(u32::from(self.measurement_sequence_number))
+        }
+    }
+}
+
+impl MovementCounter for SensorValues {
+    fn movement_counter(&self) -> Option<u32> {
+        if self.movement_counter == 0xFF {
+            None
+        } else {
+            Some(u32::from(self.movement_counter))
+        }
+    }
+}
+
+impl Pressure for SensorValues {
+    fn pressure_as_pascals(&self) -> Option<u32> {
+        if self.pressure == 0xFFFF {
+            None
+        } else {
+            Some(u32::from(self.pressure) + 50_000)
+        }
+    }
+}
+
+impl Temperature for SensorValues {
+    fn temperature_as_millikelvins(&self) -> Option<u32> {
+        if self.temperature == i16::MIN {
+            None
+        } else {
+            let temperature = i64::from(self.temperature) * 5;
+            u32::try_from(temperature + i64::from(Self::ZERO_CELSIUS_IN_MILLIKELVINS)).ok()
+        }
+    }
+}
+
+impl TransmitterPower for SensorValues {
+    fn tx_power_as_dbm(&self) -> Option<i8> {
+        let raw_value = i8::try_from(self.power_info & 0x1F).ok()?;
+
+        if raw_value == 31 {
+            None
+        } else {
+            Some(raw_value * 2 - 40)
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use aes::cipher::BlockEncrypt;
+
+    use super::*;
+
+    pub(crate) const KEY: EncryptionKey = EncryptionKey::new([
+        0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88, 0x09, 0xCF, 0x4F,
+        0x3C,
+    ]);
+
+    /// Same measurements as in the data format 5 test vector, with zero reserved bytes
+    pub(crate) const PLAINTEXT: [u8; 16] = [
+        0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0xAC, 0x36, 0x42, 0x00, 0xCD, 0x00, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+
+    pub(crate) const MAC_ADDRESS: [u8; 6] = [0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F];
+
+    /// Builds a payload including the format version by encrypting the block with the key
+    pub(crate) fn encrypt(plaintext: [u8; 16], key: &EncryptionKey) -> [u8; 24] {
+        let mut block = GenericArray::from(plaintext);
+        Aes128::new(&GenericArray::from(key.0)).encrypt_block(&mut block);
+
+        let mut payload = [0; 24];
+        payload[0] = SensorValues::VERSION;
+        payload[1..17].copy_from_slice(&block);
+        payload[17] = crc8(&plaintext);
+        payload[18..].copy_from_slice(&MAC_ADDRESS);
+        payload
+    }
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::{testing::*, *};
+
+    use crate::formats::testing::test_measurement_trait_methods;
+
+    fn decrypt(payload: &[u8; 24], key: &EncryptionKey) -> Result<SensorValues, ParseError> {
+        SensorValues::decrypt(payload[1..].try_into().unwrap(), key)
+    }
+
+    #[test]
+    fn crc8_check_value() {
+        assert_eq!(crc8(b"123456789"), 0xF4);
+    }
+
+    test_measurement_trait_methods! {
+        test valid_values {
+            values: decrypt(&encrypt(PLAINTEXT, &KEY), &KEY).unwrap(),
+            expected: {
+                acceleration_vector_as_milli_g: None,
+                battery_potential_as_millivolts: Some(2977),
+                humidity_as_ppm: Some(534_900),
+                mac_address: Some(Mac::new(MAC_ADDRESS)),
+                measurement_sequence_number: Some(205),
+                movement_counter: Some(66),
+                pressure_as_pascals: Some(100_044),
+                temperature_as_millicelsius: Some(24_300),
+                tx_power_as_dbm: Some(4),
+            },
+        }
+
+        test not_available_values {
+            values: decrypt(
+                &encrypt(
+                    [
+                        0x80, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00,
+                        0x00, 0x00, 0x00, 0x00,
+                    ],
+                    &KEY,
+                ),
+                &KEY,
+            )
+            .unwrap(),
+            expected: {
+                battery_potential_as_millivolts: None,
+                humidity_as_ppm: None,
+                measurement_sequence_number: None,
+                movement_counter: None,
+                pressure_as_pascals: None,
+                temperature_as_millicelsius: None,
+                tx_power_as_dbm: None,
+            },
+        }
+    }
+
+    #[test]
+    fn aes_test_vector() {
+        // First ECB-AES128 block of NIST SP 800-38A F.1.2, independent of `encrypt`
+        let ciphertext = [
+            0x3A, 0xD7, 0x7B, 0xB4, 0x0D, 0x7A, 0x36, 0x60, 0xA8, 0x9E, 0xCA, 0xF3, 0x24, 0x66,
+            0xEF, 0x97,
+        ];
+        let plaintext = [
+            0x6B, 0xC1, 0xBE, 0xE2, 0x2E, 0x40, 0x9F, 0x96, 0xE9, 0x3D, 0x7E, 0x11, 0x73, 0x93,
+            0x17, 0x2A,
+        ];
+        let mut payload = [0; SensorValues::SIZE];
+        payload[..16].copy_from_slice(&ciphertext);
+        payload[16] = crc8(&plaintext);
+        payload[17..].copy_from_slice(&MAC_ADDRESS);
+
+        let values = SensorValues::decrypt(&payload, &KEY).unwrap();
+        assert_eq!(values.temperature, 0x6BC1);
+        assert_eq!(values.humidity, 0xBEE2);
+        assert_eq!(values.pressure, 0x2E40);
+        assert_eq!(values.power_info, 0x9F96);
+        assert_eq!(values.movement_counter, 0xE9);
+        assert_eq!(values.measurement_sequence_number, 0x3D7E);
+    }
+
+    #[test]
+    fn wrong_key() {
+        let payload = encrypt(PLAINTEXT, &KEY);
+        let key = EncryptionKey::new([0; 16]);
+
+        assert_eq!(decrypt(&payload, &key), Err(ParseError::DecryptionFailed));
+    }
+
+    #[test]
+    fn invalid_checksum() {
+        let mut payload = encrypt(PLAINTEXT, &KEY);
+        payload[17] ^= 0x01;
+
+        assert_eq!(
+            decrypt(&payload, &KEY),
+            Err(ParseError::InvalidChecksum(payload[17], crc8(&PLAINTEXT)))
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    gateway::{
+        history::{deserialize_timestamp, InvalidEntry},
+        mqtt::deserialize_data,
+    },
+    Mac, Reading, SensorValues,
+};
+
+/// Response of the `get` or `sensors-dense` endpoint of Ruuvi Cloud API
+///
+/// The `get` endpoint returns measurements of a single sensor and the `sensors-dense` endpoint
+/// returns measurements of all sensors of the user. Both are parsed into measurements grouped by
+/// the MAC address of the sensor. Error responses of the API fail to parse, but measurements which
+/// can not be parsed are listed in `invalid` instead of failing the whole response.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(try_from = "Response")]
+pub struct CloudResponse {
+    /// Measurements of each sensor in the order they appear in the response
+    pub sensors: BTreeMap<Mac, Vec<CloudMeasurement>>,
+    /// Sensors and measurements which could not be parsed
+    pub invalid: Vec<InvalidEntry>,
+}
+
+/// Measurement stored in Ruuvi Cloud
+#[derive(Clone, Debug, PartialEq)]
+pub struct CloudMeasurement {
+    /// MAC address of the gateway which relayed the measurement
+    pub gateway: Option<Mac>,
+    /// Time, signal strength and values of the measurement
+    pub reading: Reading<SensorValues>,
+}
+
+impl CloudResponse {
+    /// Returns readings of each sensor without gateway addresses
+    #[must_use]
+    pub fn into_readings(self) -> BTreeMap<Mac, Vec<Reading<SensorValues>>> {
+        self.sensors
+            .into_iter()
+            .map(|(sensor, measurements)| {
+                let readings = measurements
+                    .into_iter()
+                    .map(|measurement| measurement.reading)
+                    .collect();
+                (sensor, readings)
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct Response {
+    result: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    data: Option<ResponseData>,
+}
+
+/// Data of a `sensors-dense` response, which has a list of sensors, or a `get` response, which
+/// has the fields of a single sensor
+#[derive(Deserialize)]
+struct ResponseData {
+    #[serde(default)]
+    sensors: Option<Vec<Sensor>>,
+    #[serde(default)]
+    sensor: Option<String>,
+    #[serde(default)]
+    measurements: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct Sensor {
+    sensor: String,
+    #[serde(default)]
+    measurements: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct Measurement {
+    #[serde(default)]
+    gwmac: Option<Mac>,
+    #[serde(default)]
+    rssi: Option<i16>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    timestamp: u64,
+    #[serde(deserialize_with = "deserialize_data")]
+    data: SensorValues,
+}
+
+impl From<Measurement> for CloudMeasurement {
+    fn from(measurement: Measurement) -> Self {
+        Self {
+            gateway: measurement.gwmac,
+            reading: Reading {
+                timestamp: measurement.timestamp,
+                rssi: measurement.rssi,
+                values: measurement.data,
+            },
+        }
+    }
+}
+
+impl TryFrom<Response> for CloudResponse {
+    type Error = String;
+
+    fn try_from(response: Response) -> Result<Self, Self::Error> {
+        let data = match (response.result.as_str(), response.data) {
+            ("success", Some(data)) => data,
+            ("success", None) => return Err("successful response without data".into()),
+            (result, _) => {
+                let error = response.error.as_deref().unwrap_or("unknown error");
+                return Err(format!("unsuccessful response ({result}): {error}"));
+            }
+        };
+
+        let sensors = match data {
+            ResponseData {
+                sensors: Some(sensors),
+                ..
+            } => sensors,
+            ResponseData {
+                sensor: Some(sensor),
+                measurements,
+                ..
+            } => vec![Sensor {
+                sensor,
+                measurements,
+            }],
+            ResponseData { .. } => {
+                return Err("response data has neither sensors nor a sensor".into());
+            }
+        };
+
+        let mut result: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        let mut invalid = Vec::new();
+        for Sensor {
+            sensor: key,
+            measurements,
+        } in sensors
+        {
+            let sensor = match key.parse::<Mac>() {
+                Ok(sensor) => sensor,
+                Err(error) => {
+                    invalid.push(InvalidEntry::new(&key, &error));
+                    continue;
+                }
+            };
+
+            let empty = measurements.is_empty();
+            let mut parsed = Vec::new();
+            for measurement in measurements {
+                match Measurement::deserialize(measurement) {
+                    Ok(measurement) => parsed.push(CloudMeasurement::from(measurement)),
+                    Err(error) => invalid.push(InvalidEntry::new(&key, &error)),
+                }
+            }
+
+            // Sensors whose measurements are all invalid are only listed as invalid
+            if empty || !parsed.is_empty() {
+                result.entry(sensor).or_default().append(&mut parsed);
+            }
+        }
+
+        Ok(Self {
+            sensors: result,
+            invalid,
+        })
+    }
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::MeasurementSequenceNumber;
+
+    const SENSORS_DENSE: &str = include_str!("fixtures/sensors-dense.json");
+
+    const SENSOR: Mac = Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]);
+    const GATEWAY: Mac = Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]);
+
+    #[test]
+    fn cloud_response_has_default_traits() {
+        crate::testing::type_has_default_traits::<CloudResponse>();
+        crate::testing::type_has_default_traits::<CloudMeasurement>();
+    }
+
+    #[test]
+    fn parse_sensors_dense_fixture() {
+        let response: CloudResponse = serde_json::from_str(SENSORS_DENSE).unwrap();
+
+        assert_eq!(response.sensors.len(), 2);
+        assert_eq!(
+            response.sensors[&Mac::new([0xE3, 0x75, 0xCF, 0x37, 0x4E, 0x23])],
+            []
+        );
+
+        let measurements = &response.sensors[&SENSOR];
+        assert_eq!(measurements.len(), 2);
+        assert_eq!(measurements[0].gateway, Some(GATEWAY));
+        assert_eq!(measurements[0].reading.timestamp, 1_653_668_027);
+        assert_eq!(measurements[0].reading.rssi, Some(-25));
+        assert_eq!(
+            measurements[1].reading.values.measurement_sequence_number(),
+            Some(10890)
+        );
+    }
+
+    #[test]
+    fn parse_get_response() {
+        let data = "\
+        {
+            \"result\": \"success\",
+            \"data\": {
+                \"sensor\": \"F4:1F:0C:28:CB:D6\",
+                \"total\": 1,
+                \"name\": \"Sauna\",
+                \"measurements\": [
+                    {
+                        \"gwmac\": \"C8:25:2D:8E:9C:2C\",
+                        \"coordinates\": \"\",
+                        \"rssi\": -25,
+                        \"timestamp\": 1653668027,
+                        \"data\": \"0201061BFF990405166455D5C6DE0008FFF403F0AE760F2A8BF41F0C28CBD6\"
+                    }
+                ]
+            }
+        }\
+        ";
+        let readings = serde_json::from_str::<CloudResponse>(data)
+            .unwrap()
+            .into_readings();
+
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings[&SENSOR][0].timestamp, 1_653_668_027);
+        assert_eq!(
+            readings[&SENSOR][0].values.measurement_sequence_number(),
+            Some(10891)
+        );
+    }
+
+    #[test]
+    fn parse_error_response() {
+        let data = "\
+        {
+            \"result\": \"error\",
+            \"error\": \"Unauthorized request\",
+            \"code\": \"ER_UNAUTHORIZED\"
+        }\
+        ";
+        let error = serde_json::from_str::<CloudResponse>(data).unwrap_err();
+
+        assert!(error.to_string().contains("Unauthorized request"));
+    }
+
+    #[test]
+    fn parse_without_sensors() {
+        let data = "{ \"result\": \"success\", \"data\": { \"total\": 0 } }";
+        let error = serde_json::from_str::<CloudResponse>(data).unwrap_err();
+
+        assert!(error.to_string().contains("neither sensors nor a sensor"));
+    }
+
+    #[test]
+    fn parse_invalid_sensors() {
+        let data = "\
+        {
+            \"result\": \"success\",
+            \"data\": { \"sensors\": { \"sensor\": \"F4:1F:0C:28:CB:D6\" } }
+        }\
+        ";
+        let error = serde_json::from_str::<CloudResponse>(data).unwrap_err();
+
+        assert!(error.to_string().starts_with("invalid type: map"));
+    }
+
+    #[test]
+    fn parse_invalid_measurements() {
+        let data = "\
+        {
+            \"result\": \"success\",
+            \"data\": { \"sensor\": \"F4:1F:0C:28:CB:D6\", \"measurements\": {} }
+        }\
+        ";
+        let error = serde_json::from_str::<CloudResponse>(data).unwrap_err();
+
+        assert!(error.to_string().starts_with("invalid type: map"));
+    }
+
+    #[test]
+    fn parse_skips_invalid_sensor_address() {
+        let data = "\
+        {
+            \"result\": \"success\",
+            \"data\": { \"sensor\": \"sauna\", \"measurements\": [] }
+        }\
+        ";
+        let response: CloudResponse = serde_json::from_str(data).unwrap();
+
+        assert_eq!(response.sensors.len(), 0);
+        assert_eq!(response.invalid.len(), 1);
+        assert_eq!(response.invalid[0].tag, "sauna");
+    }
+
+    #[test]
+    fn parse_skips_invalid_measurement_data() {
+        let data = "\
+        {
+            \"result\": \"success\",
+            \"data\": {
+                \"sensor\": \"F4:1F:0C:28:CB:D6\",
+                \"measurements\": [
+                    { \"timestamp\": 1653668027, \"data\": \"020106\" },
+                    {
+                        \"timestamp\": 1653668327,
+                        \"data\": \"0201061BFF990405166455D5C6DE0008FFF403F0AE760F2A8BF41F0C28CBD6\"
+                    }
+                ]
+            }
+        }\
+        ";
+        let response: CloudResponse = serde_json::from_str(data).unwrap();
+
+        assert_eq!(response.sensors[&SENSOR].len(), 1);
+        assert_eq!(
+            response.sensors[&SENSOR][0].reading.timestamp,
+            1_653_668_327
+        );
+        assert_eq!(response.invalid.len(), 1);
+        assert_eq!(response.invalid[0].tag, "F4:1F:0C:28:CB:D6");
+    }
+
+    #[test]
+    fn parse_skips_sensor_without_valid_measurements() {
+        let data = "\
+        {
+            \"result\": \"success\",
+            \"data\": {
+                \"sensor\": \"F4:1F:0C:28:CB:D6\",
+                \"measurements\": [
+                    { \"timestamp\": 1653668027, \"data\": \"020106\" },
+                    { \"timestamp\": 1653668327, \"data\": \"zz\" }
+                ]
+            }
+        }\
+        ";
+        let response: CloudResponse = serde_json::from_str(data).unwrap();
+
+        assert_eq!(response.sensors.len(), 0);
+        assert_eq!(response.invalid.len(), 2);
+        assert_eq!(response.invalid[1].tag, "F4:1F:0C:28:CB:D6");
+    }
+}
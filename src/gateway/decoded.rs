@@ -0,0 +1,297 @@
+use alloc::vec::Vec;
+
+use serde::Deserialize;
+
+use crate::{
+    decimal::scale, Acceleration, AccelerationVector, BatteryPotential, Field, Humidity, Mac,
+    MacAddress, MeasurementSequenceNumber, MovementCounter, Pressure, SensorValues, Temperature,
+    TransmitterPower,
+};
+
+/// Measurements decoded by Ruuvi Gateway, published in MQTT payload next to or instead of the
+/// raw advertisement data
+///
+/// Units follow the gateway: temperature in Celsius, humidity in percent, pressure in pascals,
+/// acceleration in G and voltage in volts.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DecodedFields {
+    pub(crate) data_format: Option<u8>,
+    temperature: Option<f64>,
+    humidity: Option<f64>,
+    pressure: Option<f64>,
+    accel_x: Option<f64>,
+    accel_y: Option<f64>,
+    accel_z: Option<f64>,
+    movement_counter: Option<u32>,
+    measurement_sequence_number: Option<u32>,
+    voltage: Option<f64>,
+    tx_power: Option<i8>,
+}
+
+impl DecodedFields {
+    /// Returns `true` if any measurement is present
+    pub(crate) fn has_measurements(&self) -> bool {
+        self.temperature.is_some()
+            || self.humidity.is_some()
+            || self.pressure.is_some()
+            || self.acceleration_vector_as_milli_g().is_some()
+            || self.movement_counter.is_some()
+            || self.measurement_sequence_number.is_some()
+            || self.voltage.is_some()
+            || self.tx_power.is_some()
+    }
+
+    /// Returns fields where the decoded measurement does not match the measurement parsed from
+    /// raw data. Decoded values are rounded by the gateway, so differences smaller than the
+    /// rounding are accepted.
+    pub(crate) fn mismatches(&self, raw: &SensorValues) -> Vec<Field> {
+        let mut mismatches = Vec::new();
+        let mut check = |field, raw: Option<i64>, decoded: Option<i64>, tolerance: i64| {
+            let mismatch = raw
+                .zip(decoded)
+                .is_some_and(|(raw, decoded)| (raw - decoded).abs() > tolerance);
+
+            if mismatch && !mismatches.contains(&field) {
+                mismatches.push(field);
+            }
+        };
+
+        check(
+            Field::Temperature,
+            raw.temperature_as_millicelsius().map(i64::from),
+            self.temperature_as_millicelsius().map(i64::from),
+            10,
+        );
+        check(
+            Field::Humidity,
+            raw.humidity_as_ppm().map(i64::from),
+            self.humidity_as_ppm().map(i64::from),
+            100,
+        );
+        check(
+            Field::Pressure,
+            raw.pressure_as_pascals().map(i64::from),
+            self.pressure_as_pascals().map(i64::from),
+            1,
+        );
+
+        let components = |vector: Option<AccelerationVector>| match vector {
+            Some(AccelerationVector(x, y, z)) => [Some(x), Some(y), Some(z)],
+            None => [None; 3],
+        };
+        let raw_acceleration = components(raw.acceleration_vector_as_milli_g());
+        let decoded_acceleration = components(self.acceleration_vector_as_milli_g());
+        for (raw, decoded) in raw_acceleration.into_iter().zip(decoded_acceleration) {
+            check(
+                Field::Acceleration,
+                raw.map(i64::from),
+                decoded.map(i64::from),
+                1,
+            );
+        }
+
+        check(
+            Field::BatteryPotential,
+            raw.battery_potential_as_millivolts().map(i64::from),
+            self.battery_potential_as_millivolts().map(i64::from),
+            1,
+        );
+        check(
+            Field::TransmitterPower,
+            raw.tx_power_as_dbm().map(i64::from),
+            self.tx_power_as_dbm().map(i64::from),
+            0,
+        );
+        check(
+            Field::MovementCounter,
+            raw.movement_counter().map(i64::from),
+            self.movement_counter().map(i64::from),
+            0,
+        );
+        check(
+            Field::MeasurementSequenceNumber,
+            raw.measurement_sequence_number().map(i64::from),
+            self.measurement_sequence_number().map(i64::from),
+            0,
+        );
+
+        mismatches
+    }
+}
+
+impl Acceleration for DecodedFields {
+    fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
+        let x = scale(self.accel_x?, 1000.0)?;
+        let y = scale(self.accel_y?, 1000.0)?;
+        let z = scale(self.accel_z?, 1000.0)?;
+
+        Some(AccelerationVector(x, y, z))
+    }
+}
+
+impl BatteryPotential for DecodedFields {
+    fn battery_potential_as_millivolts(&self) -> Option<u16> {
+        scale(self.voltage?, 1000.0)
+    }
+}
+
+impl Humidity for DecodedFields {
+    fn humidity_as_ppm(&self) -> Option<u32> {
+        scale(self.humidity?, 10_000.0)
+    }
+}
+
+impl MacAddress for DecodedFields {
+    fn mac_address(&self) -> Option<Mac> {
+        None
+    }
+}
+
+impl MeasurementSequenceNumber for DecodedFields {
+    fn measurement_sequence_number(&self) -> Option<u32> {
+        self.measurement_sequence_number
+    }
+}
+
+impl MovementCounter for DecodedFields {
+    fn movement_counter(&self) -> Option<u32> {
+        self.movement_counter
+    }
+}
+
+impl Pressure for DecodedFields {
+    fn pressure_as_pascals(&self) -> Option<u32> {
+        scale(self.pressure?, 1.0)
+    }
+}
+
+impl Temperature for DecodedFields {
+    fn temperature_as_millikelvins(&self) -> Option<u32> {
+        let temperature: i64 = scale(self.temperature?, 1000.0)?;
+        u32::try_from(temperature + i64::from(Self::ZERO_CELSIUS_IN_MILLIKELVINS)).ok()
+    }
+}
+
+impl TransmitterPower for DecodedFields {
+    fn tx_power_as_dbm(&self) -> Option<i8> {
+        self.tx_power
+    }
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::formats::testing::test_measurement_trait_methods;
+
+    const DECODED: &str = "\
+    {
+        \"dataFormat\": 5,
+        \"temperature\": 24.3,
+        \"humidity\": 53.49,
+        \"pressure\": 100044,
+        \"accelX\": 0.004,
+        \"accelY\": -0.004,
+        \"accelZ\": 1.036,
+        \"movementCounter\": 66,
+        \"voltage\": 2.977,
+        \"txPower\": 4,
+        \"measurementSequenceNumber\": 205
+    }\
+    ";
+
+    test_measurement_trait_methods! {
+        test decoded_fields {
+            values: serde_json::from_str::<DecodedFields>(DECODED).unwrap(),
+            expected: {
+                acceleration_vector_as_milli_g: Some(AccelerationVector(4, -4, 1036)),
+                battery_potential_as_millivolts: Some(2977),
+                humidity_as_ppm: Some(534_900),
+                mac_address: None,
+                measurement_sequence_number: Some(205),
+                movement_counter: Some(66),
+                pressure_as_pascals: Some(100_044),
+                temperature_as_millicelsius: Some(24_300),
+                tx_power_as_dbm: Some(4),
+            },
+        }
+
+        test negative_temperature {
+            values: DecodedFields {
+                temperature: Some(-12.345),
+                ..DecodedFields::default()
+            },
+            expected: {
+                temperature_as_millicelsius: Some(-12_345),
+            },
+        }
+
+        test partial_acceleration {
+            values: DecodedFields {
+                accel_x: Some(1.0),
+                accel_y: Some(1.0),
+                ..DecodedFields::default()
+            },
+            expected: {
+                acceleration_vector_as_milli_g: None,
+            },
+        }
+
+        test out_of_range_values {
+            values: DecodedFields {
+                accel_x: Some(100.0),
+                accel_y: Some(0.0),
+                accel_z: Some(0.0),
+                voltage: Some(-1.0),
+                humidity: Some(f64::NAN),
+                ..DecodedFields::default()
+            },
+            expected: {
+                acceleration_vector_as_milli_g: None,
+                battery_potential_as_millivolts: None,
+                humidity_as_ppm: None,
+            },
+        }
+    }
+
+    #[test]
+    fn has_measurements() {
+        let decoded: DecodedFields = serde_json::from_str(DECODED).unwrap();
+        let empty: DecodedFields = serde_json::from_str("{ \"dataFormat\": 5 }").unwrap();
+
+        assert!(decoded.has_measurements());
+        assert!(!empty.has_measurements());
+    }
+
+    #[test]
+    fn no_mismatches() {
+        let decoded: DecodedFields = serde_json::from_str(DECODED).unwrap();
+        let raw = SensorValues::from_measurements(&decoded);
+
+        assert_eq!(decoded.mismatches(&raw), []);
+    }
+
+    #[test]
+    fn mismatches() {
+        let decoded: DecodedFields = serde_json::from_str(DECODED).unwrap();
+        let raw = SensorValues::from_measurements(&DecodedFields {
+            temperature: Some(24.32),
+            accel_y: Some(0.004),
+            accel_z: Some(1.0),
+            movement_counter: Some(67),
+            tx_power: None,
+            ..decoded.clone()
+        });
+
+        assert_eq!(
+            decoded.mismatches(&raw),
+            [
+                Field::Temperature,
+                Field::Acceleration,
+                Field::MovementCounter
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize,
+};
+use serde_json::Value;
+
+use crate::{gateway::mqtt::deserialize_data, Mac, Reading, SensorValues};
+
+/// Response of the `/history` HTTP endpoint of Ruuvi Gateway
+///
+/// The response contains advertisements buffered by the gateway, grouped by the MAC address of the
+/// tag. Readings of each tag are in the order they appear in the response.
+///
+/// Entries which can not be parsed, such as advertisements of other than Ruuvi devices, do not
+/// fail the whole response. They are listed in `invalid` instead.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(from = "Response")]
+pub struct History {
+    /// MAC address of the gateway
+    pub gateway: Option<Mac>,
+    /// Time of the response as seconds since Unix epoch
+    pub timestamp: Option<u64>,
+    /// Readings of each tag
+    pub tags: BTreeMap<Mac, Vec<Reading<SensorValues>>>,
+    /// Entries which could not be parsed
+    pub invalid: Vec<InvalidEntry>,
+}
+
+/// Entry of a response which could not be parsed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidEntry {
+    /// Key of the tag or the sensor in the response, normally its MAC address
+    pub tag: String,
+    /// Description of the error
+    pub error: String,
+}
+
+impl InvalidEntry {
+    pub(crate) fn new(tag: &str, error: &impl ToString) -> Self {
+        Self {
+            tag: tag.into(),
+            error: error.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Response {
+    data: ResponseData,
+}
+
+#[derive(Deserialize)]
+struct ResponseData {
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    timestamp: Option<u64>,
+    #[serde(default)]
+    gw_mac: Option<Mac>,
+    tags: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(default)]
+    rssi: Option<i16>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    timestamp: u64,
+    #[serde(deserialize_with = "deserialize_data")]
+    data: SensorValues,
+}
+
+impl From<Entry> for Reading<SensorValues> {
+    fn from(entry: Entry) -> Self {
+        Reading {
+            timestamp: entry.timestamp,
+            rssi: entry.rssi,
+            values: entry.data,
+        }
+    }
+}
+
+impl From<Response> for History {
+    fn from(response: Response) -> Self {
+        let ResponseData {
+            timestamp,
+            gw_mac,
+            tags: entries,
+        } = response.data;
+        let mut tags: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        let mut invalid = Vec::new();
+
+        for (key, entries) in entries {
+            let tag = match key.parse::<Mac>() {
+                Ok(tag) => tag,
+                Err(error) => {
+                    invalid.push(InvalidEntry::new(&key, &error));
+                    continue;
+                }
+            };
+            let entries = match entries {
+                Value::Array(entries) => entries,
+                entry => vec![entry],
+            };
+
+            let mut readings = Vec::new();
+            for entry in entries {
+                match Entry::deserialize(entry) {
+                    Ok(entry) => readings.push(Reading::from(entry)),
+                    Err(error) => invalid.push(InvalidEntry::new(&key, &error)),
+                }
+            }
+
+            // Tags without any valid entry are only listed as invalid
+            if !readings.is_empty() {
+                tags.entry(tag).or_default().append(&mut readings);
+            }
+        }
+
+        Self {
+            gateway: gw_mac,
+            timestamp,
+            tags,
+            invalid,
+        }
+    }
+}
+
+/// Gateway firmware encodes timestamps as strings, but numbers are accepted too
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Timestamp {
+    Number(u64),
+    String(String),
+}
+
+pub(crate) fn deserialize_timestamp<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u64, D::Error> {
+    match Timestamp::deserialize(deserializer)? {
+        Timestamp::Number(timestamp) => Ok(timestamp),
+        Timestamp::String(timestamp) => timestamp.parse().map_err(|_| {
+            D::Error::invalid_value(Unexpected::Str(&timestamp), &"a Unix timestamp in seconds")
+        }),
+    }
+}
+
+fn deserialize_optional_timestamp<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    deserialize_timestamp(deserializer).map(Some)
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MacAddress, MeasurementSequenceNumber, Temperature};
+
+    /// Hand-written response in the format of the gateway `/history` endpoint, not a capture of
+    /// real traffic
+    const FIXTURE: &str = include_str!("fixtures/history.json");
+
+    #[test]
+    fn history_has_default_traits() {
+        crate::testing::type_has_default_traits::<History>();
+        crate::testing::type_has_default_traits::<InvalidEntry>();
+    }
+
+    #[test]
+    fn parse_history_fixture() {
+        let history: History = serde_json::from_str(FIXTURE).unwrap();
+
+        assert_eq!(
+            history.gateway,
+            Some(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]))
+        );
+        assert_eq!(history.timestamp, Some(1_653_668_040));
+        assert_eq!(history.tags.len(), 3);
+        assert_eq!(history.invalid, []);
+
+        let readings = &history.tags[&Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])];
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings[0].timestamp, 1_653_668_027);
+        assert_eq!(readings[0].rssi, Some(-25));
+        assert_eq!(
+            readings[0].values.mac_address(),
+            Some(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]))
+        );
+        assert_eq!(
+            readings[0].values.measurement_sequence_number(),
+            Some(10891)
+        );
+
+        let readings = &history.tags[&Mac::new([0xC2, 0x5E, 0x1A, 0x4B, 0x7D, 0x90])];
+        assert_eq!(readings[0].rssi, Some(-84));
+        assert_eq!(readings[0].values.mac_address(), None);
+        assert_eq!(readings[0].values.temperature_as_millicelsius(), Some(1690));
+    }
+
+    #[test]
+    fn parse_history_multiple_readings_per_tag() {
+        let data = "\
+        {
+            \"data\": {
+                \"tags\": {
+                    \"F4:1F:0C:28:CB:D6\": [
+                        {
+                            \"rssi\": -25,
+                            \"timestamp\": 1653668027,
+                            \"data\": \"0201061BFF990405166455D5C6DE0008FFF403F0AE760F2A8BF41F0C28CBD6\"
+                        },
+                        {
+                            \"timestamp\": \"1653668037\",
+                            \"data\": \"0201061BFF990405166455D5C6DE0008FFF403F0AE760F2A8CF41F0C28CBD6\"
+                        }
+                    ]
+                }
+            }
+        }\
+        ";
+        let history: History = serde_json::from_str(data).unwrap();
+        let readings = &history.tags[&Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])];
+
+        assert_eq!(history.gateway, None);
+        assert_eq!(history.timestamp, None);
+        assert_eq!(
+            readings
+                .iter()
+                .map(|reading| (reading.timestamp, reading.rssi))
+                .collect::<Vec<_>>(),
+            [(1_653_668_027, Some(-25)), (1_653_668_037, None)]
+        );
+        assert_eq!(
+            readings[1].values.measurement_sequence_number(),
+            Some(10892)
+        );
+    }
+
+    #[test]
+    fn parse_history_skips_invalid_entries() {
+        let data = "\
+        {
+            \"data\": {
+                \"tags\": {
+                    \"tag\": {
+                        \"timestamp\": \"1653668027\",
+                        \"data\": \"02010611FF990403170145355803E804E705E60886\"
+                    },
+                    \"F4:1F:0C:28:CB:D6\": [
+                        {
+                            \"timestamp\": \"yesterday\",
+                            \"data\": \"02010611FF990403170145355803E804E705E60886\"
+                        },
+                        {
+                            \"timestamp\": \"1653668027\",
+                            \"data\": \"02010611FF990403170145355803E804E705E60886\"
+                        }
+                    ],
+                    \"D0:4E:2C:59:31:6B\": {
+                        \"timestamp\": \"1653668027\",
+                        \"data\": \"0201061AFF4C000215FDA50693A4E24FB1AFCFC6EB0764782527114FE2C5\"
+                    }
+                }
+            }
+        }\
+        ";
+        let history: History = serde_json::from_str(data).unwrap();
+
+        assert_eq!(
+            history.tags[&Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])]
+                .iter()
+                .map(|reading| reading.timestamp)
+                .collect::<Vec<_>>(),
+            [1_653_668_027]
+        );
+        assert_eq!(
+            history
+                .invalid
+                .iter()
+                .map(|entry| entry.tag.as_str())
+                .collect::<Vec<_>>(),
+            ["D0:4E:2C:59:31:6B", "F4:1F:0C:28:CB:D6", "tag"]
+        );
+        assert!(!history
+            .tags
+            .contains_key(&Mac::new([0xD0, 0x4E, 0x2C, 0x59, 0x31, 0x6B])));
+        assert!(history.invalid[1].error.contains("yesterday"));
+        assert!(history.invalid[2].error.contains("Invalid MAC address"));
+    }
+
+    #[test]
+    fn parse_history_invalid_shape() {
+        let data = "{ \"data\": { \"tags\": [] } }";
+        let error = serde_json::from_str::<History>(data).unwrap_err();
+
+        assert!(error.to_string().starts_with("invalid type: sequence"));
+    }
+}
@@ -1,18 +1,23 @@
 /*!
 
-This module implements data formats used by Ruuvi Gateway for relaying RuuviTag advertisements. At
-the moment, only the data format used in MQTT message payloads is implemented. For a complete
-description of the payload formats, read [Ruuvi Gateway data format documentation][1].
+This module implements data formats used by Ruuvi Gateway for relaying RuuviTag advertisements over
+MQTT and HTTP, and responses of Ruuvi Cloud API, which stores advertisements relayed by gateways.
+For a complete description of the payload formats, read [Ruuvi Gateway data format
+documentation][1].
 
 [1]: https://docs.ruuvi.com/gw-data-formats
 
 # Parsing Ruuvi Gateway MQTT message payload
 
-At the moment, only the `data` field is parsed from the payload although it may contain other
-fields too.
+The gateway can publish the raw advertisement in the `data` field, measurements decoded by the
+gateway in fields like `temperature` and `humidity`, or both. Values are parsed from the `data`
+field when it is present, and decoded measurements are used otherwise. When both are present,
+fields whose decoded measurement does not match the raw advertisement are listed in
+[`MqttData::mismatches`]. Other fields of the payload are ignored.
 
-Parsing the payload may fail if the message payload is invalid or the `data` field of the payload
-does not contain a valid manufacturer data packet with the correct manufacturer id. The returned
+Parsing the payload may fail if the message payload is invalid, the `data` field of the payload
+does not contain a valid manufacturer data packet with the correct manufacturer id, or the payload
+contains neither the `data` field nor any decoded measurements. The returned
 error type is [`JsonError`], which is re-exported [`serde_json::Error`].
 
 [`JsonError`]: crate::gateway::JsonError
@@ -63,17 +68,214 @@ In addition to [`from_json_str`], [`from_json_slice`] and [`from_json_reader`] f
 used to parse structures from bytes or a reader respectively. All of these functions are
 re-exported from [`serde_json`].
 
+Payloads without the `data` field are parsed from the decoded measurements.
+
+```rust
+use ruuvi_sensor_protocol::{gateway::{from_json_str, MqttData}, Humidity, Temperature};
+# use ruuvi_sensor_protocol::gateway::JsonError;
+
+let data = "
+{
+    \"dataFormat\": 5,
+    \"temperature\": 24.3,
+    \"humidity\": 53.49
+}
+";
+
+let mqtt_data: MqttData = from_json_str(data)?;
+
+assert_eq!(mqtt_data.data_format, Some(5));
+assert_eq!(mqtt_data.data.temperature_as_millicelsius(), Some(24_300));
+assert_eq!(mqtt_data.data.humidity_as_ppm(), Some(534_900));
+assert!(mqtt_data.mismatches.is_empty());
+# Ok::<(), JsonError>(())
+```
+
 [`MqttData`]: crate::gateway::MqttData
+[`MqttData::mismatches`]: crate::gateway::MqttData::mismatches
 [`SensorValues`]: crate::SensorValues
 [`serde_json`]: serde_json
 
+# Parsing MQTT topics
+
+Ruuvi Gateway publishes advertisements of each tag to a topic like `ruuvi/<gateway MAC>/<tag MAC>`.
+[`MqttTopic`] parses the prefix and the MAC addresses from a topic, and [`parse_mqtt_message`]
+parses both the topic and the payload of a message. This way, the MAC address of a tag is known
+even when the data format of the payload does not contain it.
+
+```rust
+use ruuvi_sensor_protocol::{gateway::parse_mqtt_message, Mac};
+# use ruuvi_sensor_protocol::gateway::MqttError;
+
+let topic = "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6";
+let payload = "
+{
+    \"data\": \"02010611FF990403170145355803E804E705E60886\"
+}
+";
+
+let message = parse_mqtt_message(topic, payload)?;
+
+assert_eq!(message.topic.prefix, "ruuvi");
+assert_eq!(message.topic.gateway, Some(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C])));
+assert_eq!(message.tag(), Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]));
+# Ok::<(), MqttError>(())
+```
+
+[`MqttTopic`]: crate::gateway::MqttTopic
+[`parse_mqtt_message`]: crate::gateway::parse_mqtt_message
+
+# Parsing gateway status and info messages
+
+In addition to advertisements, the gateway publishes its connection state to `<prefix>/gw_status`
+and a retained info message to `<prefix>/gw_info`. [`parse_gateway_message`] classifies any
+message by its topic into a [`GatewayMessage`], so that gateway liveness can be monitored from the
+same stream of messages as tag data.
+
+```rust
+use ruuvi_sensor_protocol::gateway::{parse_gateway_message, GatewayMessage};
+# use ruuvi_sensor_protocol::gateway::MqttError;
+
+let info = parse_gateway_message(
+    "ruuvi/C8:25:2D:8E:9C:2C/gw_info",
+    "{ \"esp_fw\": \"v1.14.3\", \"nrf_fw\": \"v1.0.0\" }",
+)?;
+let unknown = parse_gateway_message("ruuvi/C8:25:2D:8E:9C:2C/config", "{}")?;
+
+assert!(matches!(info, GatewayMessage::Info { .. }));
+assert!(matches!(unknown, GatewayMessage::Unknown { .. }));
+# Ok::<(), MqttError>(())
+```
+
+[`GatewayMessage`]: crate::gateway::GatewayMessage
+[`parse_gateway_message`]: crate::gateway::parse_gateway_message
+
+# Decoding a stream of MQTT messages
+
+When `stream` feature is enabled, [`mqtt_readings`] adapts any [`Stream`] of `(topic, payload)`
+messages, for example from an async MQTT client, into a stream of decoded readings. Messages to
+other than tag topics are skipped, and messages which fail to parse yield an error without ending
+the stream. See [`mqtt_readings`] for an example.
+
+[`mqtt_readings`]: crate::gateway::mqtt_readings
+[`Stream`]: futures_core::Stream
+
+# Parsing `/history` HTTP responses
+
+The gateway buffers advertisements and serves them from its local `/history` HTTP endpoint. The
+response can be parsed into a [`History`] structure, which contains timestamped readings of each
+tag. Entries which can not be parsed, such as advertisements of other devices when the gateway does
+not filter them, are listed separately in [`History::invalid`] instead of failing the response.
+
+```rust
+use ruuvi_sensor_protocol::{gateway::{from_json_str, History}, Mac, Temperature};
+# use ruuvi_sensor_protocol::gateway::JsonError;
+
+let response = "
+{
+    \"data\": {
+        \"timestamp\": \"1653668040\",
+        \"gw_mac\": \"C8:25:2D:8E:9C:2C\",
+        \"tags\": {
+            \"F4:1F:0C:28:CB:D6\": {
+                \"rssi\": -25,
+                \"timestamp\": \"1653668027\",
+                \"data\": \"02010611FF990403170145355803E804E705E60886\"
+            }
+        }
+    }
+}
+";
+
+let history: History = from_json_str(response)?;
+let readings = &history.tags[&Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])];
+
+assert_eq!(readings[0].timestamp, 1_653_668_027);
+assert_eq!(readings[0].rssi, Some(-25));
+assert_eq!(readings[0].values.temperature_as_millicelsius(), Some(1690));
+# Ok::<(), JsonError>(())
+```
+
+[`History`]: crate::gateway::History
+[`History::invalid`]: crate::gateway::History::invalid
+
+# Receiving HTTP requests from the gateway
+
+In custom HTTP server mode, the gateway posts advertisements in the same format as the `/history`
+response. When `http-receiver` feature is enabled, [`receiver`] and [`channel_receiver`] return an
+[axum] router which checks the basic or bearer authentication configured in the gateway, parses
+each request into a [`History`], and passes it to a callback or a channel. Entries which can not be
+parsed are listed in [`History::invalid`] without rejecting the request.
+
+[`receiver`]: crate::gateway::receiver
+[`channel_receiver`]: crate::gateway::channel_receiver
+[axum]: https://docs.rs/axum
+
+# Parsing Ruuvi Cloud API responses
+
+Responses of the `get` and `sensors-dense` endpoints of Ruuvi Cloud API contain the same
+hex-encoded advertisements as gateway payloads. Both are parsed into a [`CloudResponse`], which
+contains the measurements of each sensor. Measurements which can not be parsed are listed
+separately like in [`History`].
+
+```rust
+use ruuvi_sensor_protocol::{gateway::{from_json_str, CloudResponse}, Mac, Temperature};
+# use ruuvi_sensor_protocol::gateway::JsonError;
+
+let response = "
+{
+    \"result\": \"success\",
+    \"data\": {
+        \"sensor\": \"F4:1F:0C:28:CB:D6\",
+        \"measurements\": [
+            {
+                \"gwmac\": \"C8:25:2D:8E:9C:2C\",
+                \"rssi\": -25,
+                \"timestamp\": 1653668027,
+                \"data\": \"02010611FF990403170145355803E804E705E60886\"
+            }
+        ]
+    }
+}
+";
+
+let response: CloudResponse = from_json_str(response)?;
+let measurements = &response.sensors[&Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])];
+
+assert_eq!(measurements[0].gateway, Some(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C])));
+assert_eq!(measurements[0].reading.values.temperature_as_millicelsius(), Some(1690));
+# Ok::<(), JsonError>(())
+```
+
+[`CloudResponse`]: crate::gateway::CloudResponse
+
 */
-pub use crate::gateway::mqtt::MqttData;
+#[cfg(feature = "http-receiver")]
+pub use crate::gateway::receiver::{channel_receiver, receiver, HttpAuth};
+#[cfg(feature = "stream")]
+pub use crate::gateway::stream::{mqtt_readings, MqttReading, MqttReadings};
+pub use crate::gateway::{
+    cloud::{CloudMeasurement, CloudResponse},
+    history::{History, InvalidEntry},
+    mqtt::{
+        parse_gateway_message, parse_mqtt_message, GatewayMessage, MqttData, MqttError, MqttMessage,
+    },
+    status::{GatewayInfo, GatewayState, GatewayStatus},
+    topic::{GatewayTopic, InvalidTopic, MqttTopic},
+};
 #[cfg(feature = "std")]
 pub use serde_json::from_reader as from_json_reader;
 pub use serde_json::{
     from_slice as from_json_slice, from_str as from_json_str, Error as JsonError,
 };
 
-mod data;
+mod cloud;
+mod decoded;
+mod history;
 mod mqtt;
+#[cfg(feature = "http-receiver")]
+mod receiver;
+mod status;
+#[cfg(feature = "stream")]
+mod stream;
+mod topic;
@@ -1,4 +1,5 @@
-use alloc::borrow::Cow;
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt::{self, Display};
 
 use serde::{
     de::{Error, Unexpected},
@@ -6,23 +7,250 @@ use serde::{
 };
 
 use crate::{
-    gateway::data::{IterPackets, Packet},
-    SensorValues,
+    advertisement::{IterPackets, Packet},
+    gateway::{
+        decoded::DecodedFields,
+        status::{GatewayInfo, GatewayStatus},
+        topic::{GatewayTopic, InvalidTopic, MqttTopic},
+    },
+    DataFormat, Field, Mac, MacAddress, SensorValues,
 };
 
 /// MQTT Message payload sent by Ruuvi Gateway
 ///
-/// At the moment, only the `data` field is parsed from the payload although it may contain other
-/// fields.
-#[expect(clippy::module_name_repetitions)]
+/// Depending on its configuration, the gateway publishes the raw advertisement in the `data`
+/// field, measurements decoded by the gateway in separate fields, or both. When both are present,
+/// values are parsed from the raw advertisement and the decoded measurements are only used for
+/// cross-checking.
 #[derive(serde::Deserialize, Debug)]
+#[serde(try_from = "Payload")]
 pub struct MqttData {
     /// RuuviTag sensor values parsed from the message payload
-    #[serde(deserialize_with = "deserialize_data")]
     pub data: SensorValues,
+    /// Data format reported by the gateway in the `dataFormat` field
+    pub data_format: Option<u8>,
+    /// Fields where measurements decoded by the gateway do not match the raw advertisement
+    pub mismatches: Vec<Field>,
 }
 
-fn deserialize_data<'de, D: serde::Deserializer<'de>>(
+#[derive(serde::Deserialize)]
+struct Payload {
+    #[serde(default, deserialize_with = "deserialize_optional_data")]
+    data: Option<SensorValues>,
+    #[serde(flatten)]
+    decoded: DecodedFields,
+}
+
+impl TryFrom<Payload> for MqttData {
+    type Error = &'static str;
+
+    fn try_from(payload: Payload) -> Result<Self, Self::Error> {
+        let Payload { data, decoded } = payload;
+
+        match data {
+            Some(data) => Ok(Self {
+                mismatches: decoded.mismatches(&data),
+                data_format: decoded.data_format,
+                data,
+            }),
+            None if decoded.has_measurements() => Ok(Self {
+                data: SensorValues::from_measurements(&decoded).with_data_format(
+                    decoded
+                        .data_format
+                        .and_then(|version| DataFormat::try_from(version).ok()),
+                ),
+                data_format: decoded.data_format,
+                mismatches: Vec::new(),
+            }),
+            None => Err("payload contains neither raw data nor decoded measurements"),
+        }
+    }
+}
+
+/// MQTT message published by Ruuvi Gateway, combining the parsed topic and payload
+#[derive(Debug)]
+pub struct MqttMessage<'a> {
+    /// Topic of the message
+    pub topic: MqttTopic<'a>,
+    /// Payload of the message
+    pub payload: MqttData,
+}
+
+impl MqttMessage<'_> {
+    /// Returns the MAC address of the tag. The address is taken from the topic, so it is
+    /// available even when the data format of the payload does not contain the address.
+    #[must_use]
+    pub fn tag(&self) -> Mac {
+        self.topic.tag
+    }
+}
+
+impl MacAddress for MqttMessage<'_> {
+    fn mac_address(&self) -> Option<Mac> {
+        Some(self.topic.tag)
+    }
+}
+
+/// Errors which can occur when parsing an MQTT message published by Ruuvi Gateway
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum MqttError {
+    /// Topic is not a Ruuvi Gateway advertisement topic
+    Topic(InvalidTopic),
+    /// Payload could not be parsed
+    Payload(serde_json::Error),
+}
+
+impl Display for MqttError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            MqttError::Topic(error) => write!(formatter, "Invalid MQTT topic: {error}"),
+            MqttError::Payload(error) => write!(formatter, "Invalid MQTT payload: {error}"),
+        }
+    }
+}
+
+impl core::error::Error for MqttError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            MqttError::Topic(error) => Some(error),
+            MqttError::Payload(error) => Some(error),
+        }
+    }
+}
+
+impl From<InvalidTopic> for MqttError {
+    fn from(error: InvalidTopic) -> Self {
+        MqttError::Topic(error)
+    }
+}
+
+impl From<serde_json::Error> for MqttError {
+    fn from(error: serde_json::Error) -> Self {
+        MqttError::Payload(error)
+    }
+}
+
+/// Parses the topic and the payload of an MQTT message published by Ruuvi Gateway.
+///
+/// # Errors
+///
+/// Returns [`MqttError::Topic`] if the topic does not end with a MAC address of a tag, and
+/// [`MqttError::Payload`] if the payload is not valid.
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::{gateway::parse_mqtt_message, Mac, MacAddress, Temperature};
+/// # use ruuvi_sensor_protocol::gateway::MqttError;
+///
+/// let topic = "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6";
+/// let payload = b"{\"data\": \"02010611FF990403170145355803E804E705E60886\"}";
+///
+/// let message = parse_mqtt_message(topic, payload)?;
+///
+/// assert_eq!(message.payload.data.mac_address(), None);
+/// assert_eq!(message.tag(), Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]));
+/// assert_eq!(message.payload.data.temperature_as_millicelsius(), Some(1690));
+/// # Ok::<(), MqttError>(())
+/// ```
+pub fn parse_mqtt_message(
+    topic: &str,
+    payload: impl AsRef<[u8]>,
+) -> Result<MqttMessage<'_>, MqttError> {
+    let topic = MqttTopic::parse(topic)?;
+    let payload = serde_json::from_slice(payload.as_ref())?;
+
+    Ok(MqttMessage { topic, payload })
+}
+
+/// Any MQTT message published by Ruuvi Gateway or on its behalf
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum GatewayMessage<'a> {
+    /// Advertisement of a tag
+    TagData(MqttMessage<'a>),
+    /// Connection state of the gateway, published to `<prefix>/gw_status`
+    Status {
+        /// Prefix and gateway MAC address of the topic
+        topic: GatewayTopic<'a>,
+        /// Payload of the message
+        status: GatewayStatus,
+    },
+    /// Information about the gateway, published to `<prefix>/gw_info`
+    Info {
+        /// Prefix and gateway MAC address of the topic
+        topic: GatewayTopic<'a>,
+        /// Payload of the message
+        info: GatewayInfo,
+    },
+    /// Message to a topic not known to be used by Ruuvi Gateway
+    Unknown {
+        /// Topic of the message
+        topic: &'a str,
+    },
+}
+
+/// Classifies and parses any MQTT message published by Ruuvi Gateway. Unlike
+/// [`parse_mqtt_message`], messages to unknown topics are not treated as errors.
+///
+/// # Errors
+///
+/// Returns [`MqttError::Payload`] if the topic is known but the payload is not valid.
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::{
+///     gateway::{parse_gateway_message, GatewayMessage, GatewayState},
+///     Mac,
+/// };
+/// # use ruuvi_sensor_protocol::gateway::MqttError;
+///
+/// let topic = "ruuvi/C8:25:2D:8E:9C:2C/gw_status";
+/// let payload = b"{\"state\": \"offline\"}";
+///
+/// match parse_gateway_message(topic, payload)? {
+///     GatewayMessage::Status { topic, status } => {
+///         assert_eq!(topic.gateway, Some(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C])));
+///         assert_eq!(status.state, GatewayState::Offline);
+///     }
+///     _ => unreachable!(),
+/// }
+/// # Ok::<(), MqttError>(())
+/// ```
+pub fn parse_gateway_message(
+    topic: &str,
+    payload: impl AsRef<[u8]>,
+) -> Result<GatewayMessage<'_>, MqttError> {
+    let payload = payload.as_ref();
+
+    if let Some(gateway_topic) = GatewayTopic::parse(topic, "gw_status") {
+        Ok(GatewayMessage::Status {
+            topic: gateway_topic,
+            status: serde_json::from_slice(payload)?,
+        })
+    } else if let Some(gateway_topic) = GatewayTopic::parse(topic, "gw_info") {
+        Ok(GatewayMessage::Info {
+            topic: gateway_topic,
+            info: serde_json::from_slice(payload)?,
+        })
+    } else {
+        match parse_mqtt_message(topic, payload) {
+            Ok(message) => Ok(GatewayMessage::TagData(message)),
+            Err(MqttError::Topic(_)) => Ok(GatewayMessage::Unknown { topic }),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+fn deserialize_optional_data<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<SensorValues>, D::Error> {
+    deserialize_data(deserializer).map(Some)
+}
+
+pub(crate) fn deserialize_data<'de, D: serde::Deserializer<'de>>(
     deserializer: D,
 ) -> Result<SensorValues, D::Error> {
     let encoded: Cow<'_, str> = Deserialize::deserialize(deserializer)?;
@@ -60,11 +288,123 @@ fn deserialize_data<'de, D: serde::Deserializer<'de>>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{MacAddress, MeasurementSequenceNumber};
+    use crate::{MacAddress, MeasurementSequenceNumber, Temperature};
 
     #[test]
     fn mqtt_data_has_default_traits() {
         crate::testing::type_has_default_traits::<MqttData>();
+        crate::testing::type_has_default_traits::<MqttMessage<'_>>();
+        crate::testing::type_has_default_traits::<MqttError>();
+        crate::testing::type_has_default_traits::<GatewayMessage<'_>>();
+    }
+
+    #[test]
+    fn parse_gateway_message_tag_data() {
+        let message = parse_gateway_message(
+            "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6",
+            "{ \"data\": \"02010611FF990403170145355803E804E705E60886\" }",
+        )
+        .unwrap();
+
+        let tag = Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]);
+        assert!(matches!(message, GatewayMessage::TagData(message) if message.tag() == tag));
+    }
+
+    #[test]
+    fn parse_gateway_message_status() {
+        let message = parse_gateway_message(
+            "ruuvi/C8:25:2D:8E:9C:2C/gw_status",
+            "{ \"state\": \"online\" }",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            message,
+            GatewayMessage::Status {
+                topic: GatewayTopic {
+                    prefix: "ruuvi",
+                    gateway: Some(gateway),
+                },
+                status: GatewayStatus {
+                    state: crate::gateway::GatewayState::Online,
+                },
+            } if gateway == Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C])
+        ));
+    }
+
+    #[test]
+    fn parse_gateway_message_info() {
+        let message = parse_gateway_message(
+            "ruuvi/gw_info",
+            "{ \"esp_fw\": \"v1.14.3\", \"nrf_fw\": \"v1.0.0\" }",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            message,
+            GatewayMessage::Info {
+                topic: GatewayTopic { prefix: "ruuvi", gateway: None },
+                info,
+            } if info.esp_fw.as_deref() == Some("v1.14.3")
+        ));
+    }
+
+    #[test]
+    fn parse_gateway_message_unknown() {
+        let message = parse_gateway_message("ruuvi/C8:25:2D:8E:9C:2C/config", "{}").unwrap();
+
+        assert!(matches!(
+            message,
+            GatewayMessage::Unknown {
+                topic: "ruuvi/C8:25:2D:8E:9C:2C/config"
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_gateway_message_invalid_status() {
+        let result = parse_gateway_message("ruuvi/C8:25:2D:8E:9C:2C/gw_status", "offline");
+
+        assert!(matches!(result, Err(MqttError::Payload(_))));
+    }
+
+    #[test]
+    fn parse_mqtt_message_with_v3_payload() {
+        let message = parse_mqtt_message(
+            "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6",
+            "{ \"data\": \"02010611FF990403170145355803E804E705E60886\" }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            message.topic.gateway,
+            Some(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]))
+        );
+        assert_eq!(message.payload.data.mac_address(), None);
+        assert_eq!(
+            message.mac_address(),
+            Some(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]))
+        );
+    }
+
+    #[test]
+    fn parse_mqtt_message_invalid_topic() {
+        let result = parse_mqtt_message(
+            "ruuvi/C8:25:2D:8E:9C:2C/gw_status",
+            "{ \"data\": \"02010611FF990403170145355803E804E705E60886\" }",
+        );
+
+        assert!(matches!(result, Err(MqttError::Topic(InvalidTopic))));
+    }
+
+    #[test]
+    fn parse_mqtt_message_invalid_payload() {
+        let result = parse_mqtt_message(
+            "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6",
+            "{ \"data\": \"020106\" }",
+        );
+
+        assert!(matches!(result, Err(MqttError::Payload(_))));
     }
 
     #[test]
@@ -83,7 +423,7 @@ mod tests {
 
         assert_eq!(
             mqtt_data.data.mac_address(),
-            Some([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])
+            Some(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]))
         );
         assert_eq!(mqtt_data.data.measurement_sequence_number(), Some(10891));
     }
@@ -105,7 +445,7 @@ mod tests {
 
         assert_eq!(
             mqtt_data.data.mac_address(),
-            Some([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])
+            Some(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]))
         );
         assert_eq!(mqtt_data.data.measurement_sequence_number(), Some(10891));
     }
@@ -126,7 +466,7 @@ mod tests {
 
         assert_eq!(
             mqtt_data.data.mac_address(),
-            Some([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])
+            Some(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]))
         );
         assert_eq!(mqtt_data.data.measurement_sequence_number(), Some(10891));
     }
@@ -147,7 +487,7 @@ mod tests {
 
         assert_eq!(
             mqtt_data.data.mac_address(),
-            Some([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6])
+            Some(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]))
         );
         assert_eq!(mqtt_data.data.measurement_sequence_number(), Some(10891));
     }
@@ -203,7 +543,7 @@ mod tests {
 
         assert_eq!(
             mqtt_data.data.mac_address(),
-            Some([0xE3, 0x75, 0xCF, 0x37, 0x4E, 0x23])
+            Some(Mac::new([0xE3, 0x75, 0xCF, 0x37, 0x4E, 0x23]))
         );
         assert_eq!(mqtt_data.data.measurement_sequence_number(), Some(17853));
     }
@@ -241,4 +581,96 @@ mod tests {
 
         mqtt_data.unwrap_err();
     }
+
+    #[test]
+    fn parse_mqtt_data_decoded_only() {
+        let data = "\
+        {
+            \"gw_mac\": \"C8:25:2D:8E:9C:2C\",
+            \"rssi\": -25,
+            \"dataFormat\": 5,
+            \"temperature\": 28.66,
+            \"humidity\": 54.9325,
+            \"pressure\": 100910,
+            \"accelX\": 0.008,
+            \"accelY\": -0.012,
+            \"accelZ\": 1.008,
+            \"movementCounter\": 15,
+            \"voltage\": 2.995,
+            \"txPower\": 4,
+            \"measurementSequenceNumber\": 10891
+        }\
+        ";
+        let mqtt_data: MqttData = serde_json::from_str(data).unwrap();
+
+        assert_eq!(mqtt_data.data_format, Some(5));
+        assert_eq!(mqtt_data.data.mac_address(), None);
+        assert_eq!(mqtt_data.data.temperature_as_millicelsius(), Some(28_660));
+        assert_eq!(mqtt_data.data.measurement_sequence_number(), Some(10891));
+        assert_eq!(mqtt_data.mismatches, []);
+    }
+
+    #[test]
+    fn parse_mqtt_data_raw_and_decoded() {
+        let data = "\
+        {
+            \"gw_mac\": \"C8:25:2D:8E:9C:2C\",
+            \"rssi\": -25,
+            \"data\": \"0201061BFF990405166455D5C6DE0008FFF403F0AE760F2A8BF41F0C28CBD6\",
+            \"dataFormat\": 5,
+            \"temperature\": 28.66,
+            \"humidity\": 54.933,
+            \"pressure\": 100910,
+            \"accelX\": 0.008,
+            \"accelY\": -0.012,
+            \"accelZ\": 1.008,
+            \"movementCounter\": 15,
+            \"voltage\": 2.995,
+            \"txPower\": 4,
+            \"measurementSequenceNumber\": 10891
+        }\
+        ";
+        let mqtt_data: MqttData = serde_json::from_str(data).unwrap();
+
+        assert_eq!(mqtt_data.data_format, Some(5));
+        assert_eq!(
+            mqtt_data.data.mac_address(),
+            Some(Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]))
+        );
+        assert_eq!(mqtt_data.mismatches, []);
+    }
+
+    #[test]
+    fn parse_mqtt_data_raw_and_decoded_mismatch() {
+        let data = "\
+        {
+            \"data\": \"0201061BFF990405166455D5C6DE0008FFF403F0AE760F2A8BF41F0C28CBD6\",
+            \"dataFormat\": 5,
+            \"temperature\": 28.5,
+            \"pressure\": 100910,
+            \"measurementSequenceNumber\": 10890
+        }\
+        ";
+        let mqtt_data: MqttData = serde_json::from_str(data).unwrap();
+
+        assert_eq!(mqtt_data.data.temperature_as_millicelsius(), Some(28_660));
+        assert_eq!(
+            mqtt_data.mismatches,
+            [Field::Temperature, Field::MeasurementSequenceNumber]
+        );
+    }
+
+    #[test]
+    fn parse_mqtt_data_no_measurements() {
+        let data = "\
+        {
+            \"gw_mac\": \"C8:25:2D:8E:9C:2C\",
+            \"rssi\": -25,
+            \"dataFormat\": 5
+        }\
+        ";
+        let mqtt_data: Result<MqttData, _> = serde_json::from_str(data);
+
+        mqtt_data.unwrap_err();
+    }
 }
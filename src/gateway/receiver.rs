@@ -0,0 +1,288 @@
+use alloc::{boxed::Box, format, string::String, sync::Arc};
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tokio::sync::mpsc;
+
+use crate::gateway::History;
+
+/// Authentication which Ruuvi Gateway is configured to send to a custom HTTP server
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HttpAuth {
+    /// Requests are accepted without authentication
+    None,
+    /// HTTP basic authentication
+    Basic {
+        /// Username configured in the gateway
+        username: String,
+        /// Password configured in the gateway
+        password: String,
+    },
+    /// Bearer token configured in the gateway
+    Bearer(String),
+}
+
+impl HttpAuth {
+    /// Returns the scheme and the credentials expected in the `Authorization` header
+    fn expected(&self) -> Option<(&'static str, String)> {
+        match self {
+            HttpAuth::None => None,
+            HttpAuth::Basic { username, password } => {
+                Some(("Basic", STANDARD.encode(format!("{username}:{password}"))))
+            }
+            HttpAuth::Bearer(token) => Some(("Bearer", token.clone())),
+        }
+    }
+}
+
+enum Delivery {
+    Callback(Box<dyn Fn(History) + Send + Sync>),
+    Channel(mpsc::Sender<History>),
+}
+
+struct Receiver {
+    authorization: Option<(&'static str, String)>,
+    delivery: Delivery,
+}
+
+impl Receiver {
+    fn router(auth: &HttpAuth, delivery: Delivery) -> Router {
+        let receiver = Receiver {
+            authorization: auth.expected(),
+            delivery,
+        };
+
+        Router::new()
+            .route("/", post(receive))
+            .with_state(Arc::new(receiver))
+    }
+
+    /// Returns `true` if the request has the expected credentials. The scheme is compared case
+    /// insensitively as required by HTTP, and the credentials in constant time.
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        let Some((expected_scheme, expected_credentials)) = &self.authorization else {
+            return true;
+        };
+
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+            .is_some_and(|(scheme, credentials)| {
+                scheme.eq_ignore_ascii_case(expected_scheme)
+                    && constant_time_eq(
+                        credentials.trim().as_bytes(),
+                        expected_credentials.as_bytes(),
+                    )
+            })
+    }
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+async fn receive(
+    State(receiver): State<Arc<Receiver>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !receiver.is_authorized(&headers) {
+        let challenge = receiver
+            .authorization
+            .as_ref()
+            .map_or("Basic", |(scheme, _)| scheme);
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, challenge)],
+        )
+            .into_response();
+    }
+
+    let history: History = match serde_json::from_slice(&body) {
+        Ok(history) => history,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid gateway payload: {error}"),
+            )
+                .into_response();
+        }
+    };
+
+    match &receiver.delivery {
+        Delivery::Callback(callback) => callback(history),
+        Delivery::Channel(sender) => {
+            if sender.send(history).await.is_err() {
+                return StatusCode::SERVICE_UNAVAILABLE.into_response();
+            }
+        }
+    }
+
+    StatusCode::OK.into_response()
+}
+
+/// Returns a router which receives advertisements posted by Ruuvi Gateway in custom HTTP server
+/// mode, and calls the callback with the readings of each request.
+///
+/// The router accepts `POST` requests to `/`, and can be nested under another path with
+/// [`Router::nest`]. Requests without the configured credentials are rejected with
+/// `401 Unauthorized`. Each tag is decoded separately, so entries which can not be parsed do not
+/// reject the request: the valid readings are passed on, and the invalid entries are listed in
+/// [`History::invalid`]. Only requests whose body is not a gateway payload at all are rejected with
+/// `400 Bad Request`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ruuvi_sensor_protocol::{
+///     gateway::{receiver, HttpAuth},
+///     Temperature,
+/// };
+///
+/// # async fn serve() -> std::io::Result<()> {
+/// let auth = HttpAuth::Bearer("secret".into());
+/// let router = receiver(&auth, |history| {
+///     for (tag, readings) in history.tags {
+///         for reading in readings {
+///             println!("{tag}: {:?}", reading.values.temperature_as_millicelsius());
+///         }
+///     }
+///     for entry in history.invalid {
+///         eprintln!("Skipped {}: {}", entry.tag, entry.error);
+///     }
+/// });
+///
+/// let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+/// axum::serve(listener, router).await
+/// # }
+/// ```
+pub fn receiver<F>(auth: &HttpAuth, callback: F) -> Router
+where
+    F: Fn(History) + Send + Sync + 'static,
+{
+    Receiver::router(auth, Delivery::Callback(Box::new(callback)))
+}
+
+/// Returns a router like [`receiver`], which sends the readings of each request to a channel
+/// instead of calling a callback.
+///
+/// The request is answered only after the readings have been sent, so a full channel slows down
+/// the gateway instead of dropping readings. If the receiving half of the channel is closed,
+/// requests are answered with `503 Service Unavailable`.
+pub fn channel_receiver(auth: &HttpAuth, sender: mpsc::Sender<History>) -> Router {
+    Receiver::router(auth, Delivery::Channel(sender))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn http_auth_has_default_traits() {
+        crate::testing::type_has_default_traits::<HttpAuth>();
+    }
+
+    fn is_authorized(auth: &HttpAuth, authorization: Option<&'static str>) -> bool {
+        let receiver = Receiver {
+            authorization: auth.expected(),
+            delivery: Delivery::Callback(Box::new(|_| {})),
+        };
+        let mut headers = HeaderMap::new();
+        if let Some(authorization) = authorization {
+            headers.insert(
+                header::AUTHORIZATION,
+                HeaderValue::from_static(authorization),
+            );
+        }
+
+        receiver.is_authorized(&headers)
+    }
+
+    macro_rules! test_is_authorized {
+        (
+            $(
+                test $name: ident {
+                    input: ($auth: expr, $authorization: expr),
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(is_authorized(&$auth, $authorization), $result);
+                }
+            )+
+        };
+    }
+
+    fn basic() -> HttpAuth {
+        HttpAuth::Basic {
+            username: "ruuvi".into(),
+            password: "secret".into(),
+        }
+    }
+
+    test_is_authorized! {
+        test no_auth {
+            input: (HttpAuth::None, None),
+            result: true,
+        }
+
+        test no_auth_ignores_header {
+            input: (HttpAuth::None, Some("Bearer token")),
+            result: true,
+        }
+
+        test basic_auth {
+            input: (basic(), Some("Basic cnV1dmk6c2VjcmV0")),
+            result: true,
+        }
+
+        test basic_auth_lowercase_scheme {
+            input: (basic(), Some("basic cnV1dmk6c2VjcmV0")),
+            result: true,
+        }
+
+        test basic_auth_wrong_password {
+            input: (basic(), Some("Basic cnV1dmk6c2VjcmVU")),
+            result: false,
+        }
+
+        test basic_auth_missing_header {
+            input: (basic(), None),
+            result: false,
+        }
+
+        test bearer_auth {
+            input: (HttpAuth::Bearer("token".into()), Some("Bearer token")),
+            result: true,
+        }
+
+        test bearer_auth_wrong_scheme {
+            input: (HttpAuth::Bearer("token".into()), Some("Basic token")),
+            result: false,
+        }
+
+        test bearer_auth_wrong_token {
+            input: (HttpAuth::Bearer("token".into()), Some("Bearer tokens")),
+            result: false,
+        }
+    }
+}
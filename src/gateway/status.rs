@@ -0,0 +1,133 @@
+use alloc::string::String;
+
+use serde::Deserialize;
+
+use crate::Mac;
+
+/// Payload of a `gw_status` message published by Ruuvi Gateway
+///
+/// The gateway publishes an online status when it connects to the broker, and the broker publishes
+/// an offline status as the last will of the gateway when the connection is lost.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub struct GatewayStatus {
+    /// Connection state of the gateway
+    pub state: GatewayState,
+}
+
+/// Connection state of Ruuvi Gateway
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GatewayState {
+    /// Gateway is connected to the broker
+    Online,
+    /// Gateway has disconnected from the broker
+    Offline,
+}
+
+/// Payload of a retained `gw_info` message published by Ruuvi Gateway
+///
+/// Only the firmware versions and the MAC address of the gateway are parsed, other fields are
+/// ignored.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct GatewayInfo {
+    /// Version of the ESP32 firmware
+    #[serde(default)]
+    pub esp_fw: Option<String>,
+    /// Version of the nRF52 firmware
+    #[serde(default)]
+    pub nrf_fw: Option<String>,
+    /// MAC address of the gateway
+    #[serde(default)]
+    pub gw_mac: Option<Mac>,
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_types_have_default_traits() {
+        crate::testing::type_has_default_traits::<GatewayStatus>();
+        crate::testing::type_has_default_traits::<GatewayState>();
+        crate::testing::type_has_default_traits::<GatewayInfo>();
+    }
+
+    macro_rules! test_parse_status {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let status: Option<GatewayStatus> = serde_json::from_str($input).ok();
+                    assert_eq!(status.map(|status| status.state), $result);
+                }
+            )+
+        };
+    }
+
+    test_parse_status! {
+        test online {
+            input: "{ \"state\": \"online\" }",
+            result: Some(GatewayState::Online),
+        }
+
+        test offline {
+            input: "{ \"state\": \"offline\" }",
+            result: Some(GatewayState::Offline),
+        }
+
+        test unknown_state {
+            input: "{ \"state\": \"rebooting\" }",
+            result: None,
+        }
+
+        test missing_state {
+            input: "{}",
+            result: None,
+        }
+    }
+
+    #[test]
+    fn parse_info() {
+        let data = "\
+        {
+            \"esp_fw\": \"v1.14.3\",
+            \"nrf_fw\": \"v1.0.0\",
+            \"gw_mac\": \"C8:25:2D:8E:9C:2C\",
+            \"storage\": {}
+        }\
+        ";
+        let info: GatewayInfo = serde_json::from_str(data).unwrap();
+
+        assert_eq!(
+            info,
+            GatewayInfo {
+                esp_fw: Some("v1.14.3".into()),
+                nrf_fw: Some("v1.0.0".into()),
+                gw_mac: Some(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C])),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_info_invalid_mac_address() {
+        let info: Result<GatewayInfo, _> = serde_json::from_str("{ \"gw_mac\": \"gateway\" }");
+
+        info.unwrap_err();
+    }
+
+    #[test]
+    fn parse_info_empty() {
+        let info: GatewayInfo = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(info, GatewayInfo::default());
+    }
+}
@@ -0,0 +1,225 @@
+use core::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{
+    gateway::{
+        mqtt::{MqttData, MqttError},
+        MqttTopic,
+    },
+    Mac, MacAddress,
+};
+
+/// Tag data decoded from an MQTT message by [`MqttReadings`]
+///
+/// Unlike [`MqttMessage`](crate::gateway::MqttMessage), the reading does not borrow the topic, so
+/// it can outlive the message it was decoded from.
+#[derive(Debug)]
+pub struct MqttReading {
+    /// MAC address of the gateway if it is included in the topic
+    pub gateway: Option<Mac>,
+    /// MAC address of the tag, taken from the topic
+    pub tag: Mac,
+    /// Payload of the message
+    pub payload: MqttData,
+}
+
+impl MacAddress for MqttReading {
+    fn mac_address(&self) -> Option<Mac> {
+        Some(self.tag)
+    }
+}
+
+pin_project! {
+    /// Stream which decodes MQTT messages published by Ruuvi Gateway, see [`mqtt_readings`]
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct MqttReadings<S> {
+        #[pin]
+        messages: S,
+    }
+}
+
+/// Decodes a stream of `(topic, payload)` MQTT messages published by Ruuvi Gateway into a stream
+/// of readings.
+///
+/// Messages to other than tag topics, such as `gw_status`, `gw_info` and unknown topics, are
+/// skipped without parsing their payload. A tag message which fails to parse yields an
+/// [`MqttError`] without ending the stream.
+///
+/// # Examples
+///
+/// ```rust
+/// use futures::{executor::block_on, stream, TryStreamExt};
+/// use ruuvi_sensor_protocol::{
+///     gateway::{mqtt_readings, MqttError},
+///     Mac, Temperature,
+/// };
+///
+/// let messages = stream::iter([
+///     (
+///         "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6",
+///         "{\"data\": \"02010611FF990403170145355803E804E705E60886\"}",
+///     ),
+///     ("ruuvi/C8:25:2D:8E:9C:2C/gw_status", "{\"state\": \"online\"}"),
+/// ]);
+///
+/// let readings: Vec<_> = block_on(mqtt_readings(messages).try_collect())?;
+///
+/// assert_eq!(readings.len(), 1);
+/// let reading = &readings[0];
+/// assert_eq!(reading.tag, Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]));
+/// assert_eq!(reading.payload.data.temperature_as_millicelsius(), Some(1690));
+/// # Ok::<(), MqttError>(())
+/// ```
+pub fn mqtt_readings<S, T, P>(messages: S) -> MqttReadings<S>
+where
+    S: Stream<Item = (T, P)>,
+    T: AsRef<str>,
+    P: AsRef<[u8]>,
+{
+    MqttReadings { messages }
+}
+
+impl<S, T, P> Stream for MqttReadings<S>
+where
+    S: Stream<Item = (T, P)>,
+    T: AsRef<str>,
+    P: AsRef<[u8]>,
+{
+    type Item = Result<MqttReading, MqttError>;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut messages = self.project().messages;
+
+        loop {
+            let Some((topic, payload)) = ready!(messages.as_mut().poll_next(context)) else {
+                return Poll::Ready(None);
+            };
+
+            if let Some(reading) = decode_message(&topic, &payload).transpose() {
+                return Poll::Ready(Some(reading));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.messages.size_hint().1)
+    }
+}
+
+/// Decodes tag data, or returns `None` if the message is not to a tag topic
+fn decode_message(
+    topic: &impl AsRef<str>,
+    payload: &impl AsRef<[u8]>,
+) -> Result<Option<MqttReading>, MqttError> {
+    let Ok(topic) = MqttTopic::parse(topic.as_ref()) else {
+        return Ok(None);
+    };
+
+    Ok(Some(MqttReading {
+        gateway: topic.gateway,
+        tag: topic.tag,
+        payload: serde_json::from_slice(payload.as_ref())?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use futures::{executor::block_on, stream, StreamExt};
+
+    use super::*;
+    use crate::Temperature;
+
+    const GATEWAY: Mac = Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]);
+    const TAG: Mac = Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]);
+    const TOPIC: &str = "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6";
+    const PAYLOAD: &str = "{\"data\": \"02010611FF990403170145355803E804E705E60886\"}";
+
+    #[test]
+    fn mqtt_readings_have_default_traits() {
+        crate::testing::type_has_default_traits::<MqttReading>();
+        crate::testing::type_has_default_traits::<MqttReadings<stream::Empty<(&str, &str)>>>();
+    }
+
+    fn collect<S: Stream<Item = (T, P)>, T: AsRef<str>, P: AsRef<[u8]>>(
+        messages: S,
+    ) -> Vec<Result<MqttReading, MqttError>> {
+        block_on(mqtt_readings(messages).collect())
+    }
+
+    #[test]
+    fn decodes_tag_data() {
+        let readings = collect(stream::iter([(TOPIC, PAYLOAD)]));
+
+        assert_eq!(readings.len(), 1);
+        assert!(matches!(
+            &readings[0],
+            Ok(MqttReading { gateway: Some(gateway), tag, payload })
+                if *gateway == GATEWAY
+                    && *tag == TAG
+                    && payload.data.temperature_as_millicelsius() == Some(1690)
+        ));
+    }
+
+    #[test]
+    fn errors_do_not_end_stream() {
+        let readings = collect(stream::iter([
+            (TOPIC, "{\"data\": \"invalid\"}"),
+            (
+                "ruuvi/C8:25:2D:8E:9C:2C/gw_status",
+                "{\"state\": \"invalid\"}",
+            ),
+            (TOPIC, PAYLOAD),
+        ]));
+
+        assert_eq!(readings.len(), 2);
+        assert!(matches!(readings[0], Err(MqttError::Payload(_))));
+        assert!(matches!(&readings[1], Ok(reading) if reading.tag == TAG));
+    }
+
+    #[test]
+    fn skips_gateway_and_unknown_topics() {
+        let readings = collect(stream::iter([
+            (
+                "ruuvi/C8:25:2D:8E:9C:2C/gw_status",
+                "{\"state\": \"online\"}",
+            ),
+            (
+                "ruuvi/C8:25:2D:8E:9C:2C/gw_info",
+                "{\"esp_fw\": \"v1.14.3\", \"nrf_fw\": \"v1.0.0\"}",
+            ),
+            ("ruuvi/C8:25:2D:8E:9C:2C/config", "{}"),
+            (TOPIC, PAYLOAD),
+        ]));
+
+        assert_eq!(readings.len(), 1);
+        assert!(matches!(&readings[0], Ok(reading) if reading.tag == TAG));
+    }
+
+    #[test]
+    fn accepts_owned_messages() {
+        let messages = vec![(String::from(TOPIC), PAYLOAD.as_bytes().to_vec())];
+        let readings = collect(stream::iter(messages));
+
+        assert!(matches!(&readings[0], Ok(reading) if reading.mac_address() == Some(TAG)));
+    }
+
+    #[test]
+    fn size_hint_follows_messages() {
+        let readings = mqtt_readings(stream::iter([(TOPIC, PAYLOAD), (TOPIC, PAYLOAD)]));
+
+        assert_eq!(readings.size_hint(), (0, Some(2)));
+    }
+
+    #[test]
+    fn empty_stream() {
+        assert!(collect(stream::empty::<(&str, &str)>()).is_empty());
+    }
+}
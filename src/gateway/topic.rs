@@ -0,0 +1,205 @@
+use core::fmt::{self, Display};
+
+use crate::Mac;
+
+/// MQTT topic used by Ruuvi Gateway for relaying RuuviTag advertisements
+///
+/// By default, the gateway publishes advertisements to `ruuvi/<gateway MAC>/<tag MAC>`. The
+/// `ruuvi` prefix can be changed in the gateway configuration, and the gateway MAC address can be
+/// left out from the topic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MqttTopic<'a> {
+    /// Topic prefix without the gateway MAC address and the trailing slash, may be empty
+    pub prefix: &'a str,
+    /// MAC address of the gateway if it is included in the topic
+    pub gateway: Option<Mac>,
+    /// MAC address of the tag
+    pub tag: Mac,
+}
+
+impl<'a> MqttTopic<'a> {
+    /// Parses an MQTT topic. MAC addresses may be separated with colons or dashes, or have no
+    /// separators at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidTopic`] if the last level of the topic is not a MAC address.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ruuvi_sensor_protocol::{gateway::MqttTopic, Mac};
+    /// # use ruuvi_sensor_protocol::gateway::InvalidTopic;
+    ///
+    /// let topic = MqttTopic::parse("home/ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6")?;
+    ///
+    /// assert_eq!(topic.prefix, "home/ruuvi");
+    /// assert_eq!(topic.gateway, Some(Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C])));
+    /// assert_eq!(topic.tag, Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]));
+    /// # Ok::<(), InvalidTopic>(())
+    /// ```
+    pub fn parse(topic: &'a str) -> Result<Self, InvalidTopic> {
+        let (rest, tag) = split_last_level(topic);
+        let tag = tag.parse().map_err(|_| InvalidTopic)?;
+        let GatewayTopic { prefix, gateway } = GatewayTopic::from_levels(rest);
+
+        Ok(Self {
+            prefix,
+            gateway,
+            tag,
+        })
+    }
+}
+
+/// Prefix and gateway MAC address of an MQTT topic used by Ruuvi Gateway for its own messages,
+/// such as `ruuvi/<gateway MAC>/gw_status`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GatewayTopic<'a> {
+    /// Topic prefix without the gateway MAC address and the trailing slash, may be empty
+    pub prefix: &'a str,
+    /// MAC address of the gateway if it is included in the topic
+    pub gateway: Option<Mac>,
+}
+
+impl<'a> GatewayTopic<'a> {
+    /// Parses the topic levels before the last level of a topic
+    fn from_levels(levels: &'a str) -> Self {
+        let (prefix, gateway) = split_last_level(levels);
+
+        match gateway.parse() {
+            Ok(gateway) => Self {
+                prefix,
+                gateway: Some(gateway),
+            },
+            Err(_) => Self {
+                prefix: levels,
+                gateway: None,
+            },
+        }
+    }
+
+    /// Parses a topic ending with the given level, returning `None` if the topic ends with a
+    /// different level
+    pub(crate) fn parse(topic: &'a str, last_level: &str) -> Option<Self> {
+        let (rest, level) = split_last_level(topic);
+        (level == last_level).then(|| Self::from_levels(rest))
+    }
+}
+
+fn split_last_level(topic: &str) -> (&str, &str) {
+    topic.rsplit_once('/').unwrap_or(("", topic))
+}
+
+/// Error returned when an MQTT topic is not a Ruuvi Gateway advertisement topic
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidTopic;
+
+impl Display for InvalidTopic {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(formatter, "Topic does not end with a MAC address of a tag")
+    }
+}
+
+impl core::error::Error for InvalidTopic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GATEWAY: Mac = Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]);
+    const TAG: Mac = Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]);
+
+    #[test]
+    fn mqtt_topic_has_default_traits() {
+        crate::testing::type_has_default_traits::<MqttTopic<'_>>();
+        crate::testing::type_has_default_traits::<GatewayTopic<'_>>();
+        crate::testing::type_has_default_traits::<InvalidTopic>();
+    }
+
+    macro_rules! test_parse_topic {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(MqttTopic::parse($input), $result);
+                }
+            )+
+        };
+    }
+
+    test_parse_topic! {
+        test default_topic {
+            input: "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6",
+            result: Ok(MqttTopic { prefix: "ruuvi", gateway: Some(GATEWAY), tag: TAG }),
+        }
+
+        test custom_prefix {
+            input: "home/sensors/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6",
+            result: Ok(MqttTopic { prefix: "home/sensors", gateway: Some(GATEWAY), tag: TAG }),
+        }
+
+        test without_gateway {
+            input: "home/sensors/F4:1F:0C:28:CB:D6",
+            result: Ok(MqttTopic { prefix: "home/sensors", gateway: None, tag: TAG }),
+        }
+
+        test without_prefix {
+            input: "C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6",
+            result: Ok(MqttTopic { prefix: "", gateway: Some(GATEWAY), tag: TAG }),
+        }
+
+        test only_tag {
+            input: "f41f0c28cbd6",
+            result: Ok(MqttTopic { prefix: "", gateway: None, tag: TAG }),
+        }
+
+        test empty_prefix_level {
+            input: "/F4:1F:0C:28:CB:D6",
+            result: Ok(MqttTopic { prefix: "", gateway: None, tag: TAG }),
+        }
+
+        test gateway_status {
+            input: "ruuvi/C8:25:2D:8E:9C:2C/gw_status",
+            result: Err(InvalidTopic),
+        }
+
+        test trailing_slash {
+            input: "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6/",
+            result: Err(InvalidTopic),
+        }
+
+        test empty {
+            input: "",
+            result: Err(InvalidTopic),
+        }
+    }
+
+    #[test]
+    fn parse_gateway_topic() {
+        assert_eq!(
+            GatewayTopic::parse("home/ruuvi/C8:25:2D:8E:9C:2C/gw_status", "gw_status"),
+            Some(GatewayTopic {
+                prefix: "home/ruuvi",
+                gateway: Some(GATEWAY),
+            })
+        );
+        assert_eq!(
+            GatewayTopic::parse("ruuvi/gw_status", "gw_status"),
+            Some(GatewayTopic {
+                prefix: "ruuvi",
+                gateway: None,
+            })
+        );
+        assert_eq!(
+            GatewayTopic::parse("ruuvi/C8:25:2D:8E:9C:2C/gw_status", "gw_info"),
+            None
+        );
+    }
+}
@@ -0,0 +1,636 @@
+/*!
+
+Parsing of advertising reports from raw HCI events.
+
+Bluetooth controllers report received advertisements in HCI LE Advertising Report events (LE Meta
+event `0x3E` with subevent `0x02`), and with extended advertising enabled in LE Extended
+Advertising Report events (subevent `0x0D`). Extended reports are needed to receive advertisements
+sent on secondary advertising channels.
+
+Only the data formats of [`SensorValues`](crate::SensorValues) are decoded. Data format E1, which
+is sent in extended advertisements, is not decoded yet and is reported as an unsupported format
+version.
+
+[`advertising_reports`] parses an event packet, for example read from an HCI user channel socket,
+and returns an iterator over the reports in the event. Each [`AdvertisingReport`] has the address,
+the address type, the signal strength and the advertising data of a device, and
+[`AdvertisingReport::decode`] decodes the Ruuvi data in the advertising data.
+
+```rust
+use ruuvi_sensor_protocol::{hci::advertising_reports, Mac, Temperature};
+
+let packet = [
+    0x04, 0x3E, 0x21, 0x02, 0x01, 0x00, 0x01, 0xD6, 0xCB, 0x28, 0x0C, 0x1F, 0xF4, 0x15, 0x02,
+    0x01, 0x06, 0x11, 0xFF, 0x99, 0x04, 0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04,
+    0xE7, 0x05, 0xE6, 0x08, 0x86, 0xC2,
+];
+
+for report in advertising_reports(&packet)? {
+    let result = report?.decode()?;
+
+    assert_eq!(result.address, Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]));
+    assert_eq!(result.rssi, Some(-62));
+    assert_eq!(result.values.temperature_as_millicelsius(), Some(1690));
+}
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+*/
+
+use core::fmt::{self, Display};
+
+use crate::{
+    decode::{decode, DecodeError, DecodeInput, Decoded, ScanResult},
+    Mac,
+};
+
+/// Packet type indicator of HCI event packets
+const EVENT_PACKET: u8 = 0x04;
+/// Event code of LE Meta event
+const LE_META_EVENT: u8 = 0x3E;
+/// Subevent code of LE Advertising Report event
+const ADVERTISING_REPORT: u8 = 0x02;
+/// Subevent code of LE Extended Advertising Report event
+const EXTENDED_ADVERTISING_REPORT: u8 = 0x0D;
+/// RSSI reported when the controller does not know the signal strength
+const RSSI_NOT_AVAILABLE: i8 = 0x7F;
+/// Mask of data status bits in the event type of an extended report
+const DATA_STATUS_MASK: u16 = 0x0060;
+
+/// Type of the address of an advertising device
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AddressType {
+    /// Public device address
+    Public,
+    /// Random device address
+    Random,
+    /// Public identity address resolved by the controller
+    PublicIdentity,
+    /// Random static identity address resolved by the controller
+    RandomIdentity,
+    /// No address was provided, only in extended reports
+    Anonymous,
+    /// Address type not defined by the specification
+    Other(u8),
+}
+
+impl From<u8> for AddressType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => AddressType::Public,
+            0x01 => AddressType::Random,
+            0x02 => AddressType::PublicIdentity,
+            0x03 => AddressType::RandomIdentity,
+            0xFF => AddressType::Anonymous,
+            other => AddressType::Other(other),
+        }
+    }
+}
+
+/// Advertisement reported by a Bluetooth controller in an advertising report event
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdvertisingReport<'a> {
+    /// `true` if the report was sent in an LE Extended Advertising Report event
+    pub extended: bool,
+    /// Event type of the report, with the bits of legacy or extended reports
+    pub event_type: u16,
+    /// Type of the address of the device
+    pub address_type: AddressType,
+    /// Address of the device
+    pub address: Mac,
+    /// Received signal strength in dBm, if known by the controller
+    pub rssi: Option<i8>,
+    /// Advertising data consisting of length-prefixed packets
+    pub data: &'a [u8],
+}
+
+impl AdvertisingReport<'_> {
+    /// Returns `false` if the advertising data of an extended report is incomplete or truncated.
+    /// Legacy reports always have complete data.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        !self.extended || self.event_type & DATA_STATUS_MASK == 0
+    }
+
+    /// Decodes the Ruuvi data in the advertising data of the report, like
+    /// [`DecodeInput::Advertisement`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError::IncompleteAdvertisement`] if the report is not complete,
+    /// [`DecodeError::NoRuuviData`] if the advertising data does not contain Ruuvi data,
+    /// [`DecodeError::InvalidAdvertisement`] if the packets of the data can not be read, and
+    /// [`DecodeError::Parse`] if Ruuvi data is found but it is not valid.
+    pub fn decode(&self) -> Result<ScanResult, DecodeError> {
+        if !self.is_complete() {
+            return Err(DecodeError::IncompleteAdvertisement);
+        }
+
+        let Decoded { values, transport } = decode(DecodeInput::Advertisement(self.data))?;
+
+        Ok(ScanResult {
+            address: self.address,
+            rssi: self.rssi.map(i16::from),
+            values,
+            transport,
+        })
+    }
+}
+
+/// Errors which can occur when parsing an HCI event packet
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HciError {
+    /// Packet is not an LE Advertising Report or an LE Extended Advertising Report event
+    NotAdvertisingReport,
+    /// Packet is shorter or longer than its parameters
+    InvalidLength,
+}
+
+impl Display for HciError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            HciError::NotAdvertisingReport => {
+                write!(formatter, "HCI event is not an LE advertising report")
+            }
+            HciError::InvalidLength => write!(formatter, "Invalid length of HCI event"),
+        }
+    }
+}
+
+impl core::error::Error for HciError {}
+
+/// Iterator over the reports of an advertising report event, returned by [`advertising_reports`]
+#[derive(Clone, Debug)]
+pub struct AdvertisingReports<'a> {
+    extended: bool,
+    remaining: u8,
+    data: &'a [u8],
+}
+
+impl<'a> AdvertisingReports<'a> {
+    fn next_report(&mut self) -> Result<AdvertisingReport<'a>, HciError> {
+        let (event_type, address_type, mut address, rssi, data) = if self.extended {
+            let [low, high, address_type] = take(&mut self.data)?;
+            let address: [u8; 6] = take(&mut self.data)?;
+            let [_primary_phy, _secondary_phy, _sid, _tx_power, rssi] = take(&mut self.data)?;
+            let _interval_and_direct_address: [u8; 9] = take(&mut self.data)?;
+            let data = take_data(&mut self.data)?;
+            (
+                u16::from_le_bytes([low, high]),
+                address_type,
+                address,
+                rssi,
+                data,
+            )
+        } else {
+            let [event_type, address_type] = take(&mut self.data)?;
+            let address: [u8; 6] = take(&mut self.data)?;
+            let data = take_data(&mut self.data)?;
+            let [rssi] = take(&mut self.data)?;
+            (event_type.into(), address_type, address, rssi, data)
+        };
+        address.reverse();
+        let rssi = i8::from_le_bytes([rssi]);
+
+        Ok(AdvertisingReport {
+            extended: self.extended,
+            event_type,
+            address_type: address_type.into(),
+            address: Mac::new(address),
+            rssi: (rssi != RSSI_NOT_AVAILABLE).then_some(rssi),
+            data,
+        })
+    }
+}
+
+impl<'a> Iterator for AdvertisingReports<'a> {
+    type Item = Result<AdvertisingReport<'a>, HciError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            if self.data.is_empty() {
+                return None;
+            }
+
+            self.data = &[];
+            return Some(Err(HciError::InvalidLength));
+        }
+
+        let report = self.next_report();
+        if report.is_ok() {
+            self.remaining -= 1;
+        } else {
+            self.remaining = 0;
+            self.data = &[];
+        }
+
+        Some(report)
+    }
+}
+
+/// Takes the next `N` bytes from the data
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], HciError> {
+    let (bytes, remaining) = data
+        .split_first_chunk::<N>()
+        .ok_or(HciError::InvalidLength)?;
+    *data = remaining;
+    Ok(*bytes)
+}
+
+/// Takes the length-prefixed advertising data of a report from the data
+fn take_data<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], HciError> {
+    let [length] = take(data)?;
+    let (advertising_data, remaining) = data
+        .split_at_checked(usize::from(length))
+        .ok_or(HciError::InvalidLength)?;
+    *data = remaining;
+    Ok(advertising_data)
+}
+
+/// Parses an HCI LE Advertising Report or LE Extended Advertising Report event packet, and
+/// returns an iterator over the reports of the event.
+///
+/// The packet may start with the HCI event packet type indicator `0x04`, like packets read from an
+/// HCI user channel socket, or directly with the event code. The reports are parsed while
+/// iterating, and the iterator ends after the first invalid report or after an error for data left
+/// over after the reports.
+///
+/// # Errors
+///
+/// Returns [`HciError::NotAdvertisingReport`] if the packet is some other event, and
+/// [`HciError::InvalidLength`] if the length of the packet does not match the length of its
+/// parameters.
+pub fn advertising_reports(packet: &[u8]) -> Result<AdvertisingReports<'_>, HciError> {
+    let packet = match packet {
+        [EVENT_PACKET, LE_META_EVENT, ..] => &packet[1..],
+        _ => packet,
+    };
+
+    match packet {
+        [LE_META_EVENT, length, parameters @ ..] => {
+            if usize::from(*length) != parameters.len() {
+                return Err(HciError::InvalidLength);
+            }
+
+            match parameters {
+                [ADVERTISING_REPORT, reports, data @ ..] => Ok(AdvertisingReports {
+                    extended: false,
+                    remaining: *reports,
+                    data,
+                }),
+                [EXTENDED_ADVERTISING_REPORT, reports, data @ ..] => Ok(AdvertisingReports {
+                    extended: true,
+                    remaining: *reports,
+                    data,
+                }),
+                [ADVERTISING_REPORT | EXTENDED_ADVERTISING_REPORT] => Err(HciError::InvalidLength),
+                _ => Err(HciError::NotAdvertisingReport),
+            }
+        }
+        _ => Err(HciError::NotAdvertisingReport),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DataFormat, ParseError, Temperature, Transport};
+
+    const ADDRESS: Mac = Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]);
+
+    /// Packet type indicator and LE Advertising Report event with manufacturer data of data
+    /// format 3
+    const LEGACY: [u8; 36] = [
+        0x04, 0x3E, 0x21, 0x02, 0x01, 0x00, 0x01, 0xD6, 0xCB, 0x28, 0x0C, 0x1F, 0xF4, 0x15, 0x02,
+        0x01, 0x06, 0x11, 0xFF, 0x99, 0x04, 0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04,
+        0xE7, 0x05, 0xE6, 0x08, 0x86, 0xC2,
+    ];
+
+    /// LE Advertising Report event with two reports, the second one without data or RSSI
+    const TWO_REPORTS: [u8; 27] = [
+        0x3E, 0x19, 0x02, 0x02, 0x00, 0x00, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0x03, 0x02, 0x01,
+        0x06, 0xC2, 0x04, 0x01, 0xD6, 0xCB, 0x28, 0x0C, 0x1F, 0xF4, 0x00, 0x7F,
+    ];
+
+    /// LE Extended Advertising Report event with manufacturer data of data format 5
+    const EXTENDED: [u8; 59] = [
+        0x3E, 0x39, 0x0D, 0x01, 0x00, 0x00, 0x01, 0xD6, 0xCB, 0x28, 0x0C, 0x1F, 0xF4, 0x01, 0x00,
+        0xFF, 0x7F, 0xB9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, 0x02, 0x01,
+        0x06, 0x1B, 0xFF, 0x99, 0x04, 0x05, 0x12, 0xFC, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF,
+        0xFC, 0x04, 0x0C, 0xAC, 0x36, 0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+    ];
+
+    /// LE Extended Advertising Report event with truncated data and unknown RSSI
+    const EXTENDED_TRUNCATED: [u8; 32] = [
+        0x3E, 0x1E, 0x0D, 0x01, 0x40, 0x00, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0xFF, 0x7F, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x03, 0xFF,
+        0x99, 0x04,
+    ];
+
+    #[test]
+    fn address_type_has_default_traits() {
+        crate::testing::type_has_default_traits::<AddressType>();
+    }
+
+    #[test]
+    fn advertising_report_has_default_traits() {
+        crate::testing::type_has_default_traits::<AdvertisingReport<'_>>();
+    }
+
+    #[test]
+    fn advertising_reports_has_default_traits() {
+        crate::testing::type_has_default_traits::<AdvertisingReports<'_>>();
+    }
+
+    #[test]
+    fn hci_error_has_default_traits() {
+        crate::testing::type_has_default_traits::<HciError>();
+    }
+
+    macro_rules! test_advertising_reports {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    results: [
+                        $($result: expr,)+
+                    ],
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let data = $input;
+                    let mut reports = advertising_reports(data.as_ref());
+
+                    $(
+                        assert_eq!(
+                            reports.as_mut().map(Iterator::next).map_err(|error| *error),
+                            $result
+                        );
+                    )+
+                }
+            )+
+        };
+    }
+
+    macro_rules! test_decode_report {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: pat,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let result = advertising_reports(&$input)
+                        .ok()
+                        .and_then(|mut reports| reports.next())
+                        .map(|report| report.map(|report| report.decode()));
+                    assert!(matches!(result, Some(Ok($result))), "{result:?}");
+                }
+            )+
+        };
+    }
+
+    test_advertising_reports! {
+        test legacy_report {
+            input: LEGACY,
+            results: [
+                Ok(Some(Ok(AdvertisingReport {
+                    extended: false,
+                    event_type: 0x00,
+                    address_type: AddressType::Random,
+                    address: ADDRESS,
+                    rssi: Some(-62),
+                    data: &LEGACY[14..35],
+                }))),
+                Ok(None),
+            ],
+        }
+
+        test without_packet_type_indicator {
+            input: &LEGACY[1..],
+            results: [
+                Ok(Some(Ok(AdvertisingReport {
+                    extended: false,
+                    event_type: 0x00,
+                    address_type: AddressType::Random,
+                    address: ADDRESS,
+                    rssi: Some(-62),
+                    data: &LEGACY[14..35],
+                }))),
+                Ok(None),
+            ],
+        }
+
+        test multiple_legacy_reports {
+            input: TWO_REPORTS,
+            results: [
+                Ok(Some(Ok(AdvertisingReport {
+                    extended: false,
+                    event_type: 0x00,
+                    address_type: AddressType::Public,
+                    address: Mac::new([0x01, 0x02, 0x03, 0x04, 0x05, 0x06]),
+                    rssi: Some(-62),
+                    data: &[0x02, 0x01, 0x06],
+                }))),
+                Ok(Some(Ok(AdvertisingReport {
+                    extended: false,
+                    event_type: 0x04,
+                    address_type: AddressType::Random,
+                    address: ADDRESS,
+                    rssi: None,
+                    data: &[],
+                }))),
+                Ok(None),
+            ],
+        }
+
+        test extended_report {
+            input: EXTENDED,
+            results: [
+                Ok(Some(Ok(AdvertisingReport {
+                    extended: true,
+                    event_type: 0x0000,
+                    address_type: AddressType::Random,
+                    address: ADDRESS,
+                    rssi: Some(-71),
+                    data: &EXTENDED[28..],
+                }))),
+                Ok(None),
+            ],
+        }
+
+        test extended_report_with_truncated_data {
+            input: EXTENDED_TRUNCATED,
+            results: [
+                Ok(Some(Ok(AdvertisingReport {
+                    extended: true,
+                    event_type: 0x0040,
+                    address_type: AddressType::Anonymous,
+                    address: Mac::new([0x00; 6]),
+                    rssi: None,
+                    data: &[0x03, 0xFF, 0x99, 0x04],
+                }))),
+                Ok(None),
+            ],
+        }
+
+        test missing_report {
+            input: [
+                0x3E, 0x0F, 0x02, 0x02, 0x00, 0x00, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0x02,
+                0x01, 0x06, 0xC2, 0x00,
+            ],
+            results: [
+                Ok(Some(Ok(AdvertisingReport {
+                    extended: false,
+                    event_type: 0x00,
+                    address_type: AddressType::Public,
+                    address: Mac::new([0x01, 0x02, 0x03, 0x04, 0x05, 0x06]),
+                    rssi: Some(-62),
+                    data: &[0x01, 0x06],
+                }))),
+                Ok(Some(Err(HciError::InvalidLength))),
+                Ok(None),
+            ],
+        }
+
+        test data_after_reports {
+            input: [0x3E, 0x03, 0x02, 0x00, 0xFF],
+            results: [
+                Ok(Some(Err(HciError::InvalidLength))),
+                Ok(None),
+            ],
+        }
+
+        test report_longer_than_event {
+            input: [
+                0x3E, 0x0C, 0x02, 0x01, 0x00, 0x00, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0x02,
+                0x01,
+            ],
+            results: [
+                Ok(Some(Err(HciError::InvalidLength))),
+                Ok(None),
+            ],
+        }
+
+        test other_event {
+            input: [0x04, 0x0E, 0x04, 0x01, 0x0C, 0x20, 0x00],
+            results: [
+                Err(HciError::NotAdvertisingReport),
+            ],
+        }
+
+        test other_le_meta_event {
+            input: [0x3E, 0x02, 0x01, 0x00],
+            results: [
+                Err(HciError::NotAdvertisingReport),
+            ],
+        }
+
+        test invalid_event_length {
+            input: &LEGACY[..35],
+            results: [
+                Err(HciError::InvalidLength),
+            ],
+        }
+
+        test missing_report_count {
+            input: [0x3E, 0x01, 0x0D],
+            results: [
+                Err(HciError::InvalidLength),
+            ],
+        }
+    }
+
+    test_decode_report! {
+        test decode_legacy_report {
+            input: LEGACY,
+            result: Ok(ScanResult {
+                address: ADDRESS,
+                rssi: Some(-62),
+                transport: Transport::ManufacturerData,
+                ..
+            }),
+        }
+
+        test decode_extended_report {
+            input: EXTENDED,
+            result: Ok(ScanResult {
+                address: ADDRESS,
+                rssi: Some(-71),
+                transport: Transport::ManufacturerData,
+                ..
+            }),
+        }
+
+        test decode_report_without_ruuvi_data {
+            input: TWO_REPORTS,
+            result: Err(DecodeError::NoRuuviData),
+        }
+
+        test decode_truncated_report {
+            input: EXTENDED_TRUNCATED,
+            result: Err(DecodeError::IncompleteAdvertisement),
+        }
+
+        test decode_incomplete_report {
+            input: {
+                let mut packet = EXTENDED;
+                packet[4] = 0x20;
+                packet
+            },
+            result: Err(DecodeError::IncompleteAdvertisement),
+        }
+
+        test decode_e1_report {
+            input: {
+                let mut packet = EXTENDED;
+                packet[35] = 0xE1;
+                packet
+            },
+            result: Err(DecodeError::Parse(ParseError::UnsupportedFormatVersion(0xE1))),
+        }
+    }
+
+    #[test]
+    fn decoded_values() {
+        let result = advertising_reports(&EXTENDED)
+            .ok()
+            .and_then(|mut reports| reports.next())
+            .and_then(Result::ok)
+            .and_then(|report| report.decode().ok());
+
+        assert_eq!(
+            result
+                .as_ref()
+                .and_then(|result| result.values.data_format()),
+            Some(DataFormat::V5)
+        );
+        assert_eq!(
+            result.and_then(|result| result.values.temperature_as_millicelsius()),
+            Some(24_300)
+        );
+    }
+
+    #[test]
+    fn is_complete() {
+        let is_complete = |packet: &[u8]| {
+            advertising_reports(packet)
+                .ok()
+                .and_then(|mut reports| reports.next())
+                .and_then(Result::ok)
+                .map(|report| report.is_complete())
+        };
+
+        assert_eq!(is_complete(&LEGACY), Some(true));
+        assert_eq!(is_complete(&EXTENDED), Some(true));
+        assert_eq!(is_complete(&EXTENDED_TRUNCATED), Some(false));
+    }
+}
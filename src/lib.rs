@@ -48,6 +48,27 @@ See [`SensorValues`] documentation for a description of each value.
 
 [`SensorValues`]: crate::SensorValues
 
+# Decoding any transport
+
+[`decode`] accepts manufacturer specific data, Eddystone service data of the legacy URL formats,
+raw advertisement data, Ruuvi Gateway payloads and Nordic UART Service notifications, and reports
+the data format and the transport along with the values.
+
+```rust
+use ruuvi_sensor_protocol::{decode, DataFormat, DecodeError, DecodeInput, Transport};
+
+let data = [
+    0x03, 0x17, 0x01, 0x45, 0x35, 0x58, 0x03, 0xE8, 0x04, 0xE7, 0x05, 0xE6, 0x08, 0x86,
+];
+let decoded = decode(DecodeInput::ManufacturerData { id: 0x0499, data: &data })?;
+
+assert_eq!(decoded.transport, Transport::ManufacturerData);
+assert_eq!(decoded.data_format(), Some(DataFormat::V3));
+# Ok::<(), DecodeError>(())
+```
+
+[`decode`]: crate::decode()
+
 # Parsing Ruuvi Gateway data formats
 
 This crate also supports parsing MQTT message payloads published by a Ruuvi Gateway.
@@ -56,26 +77,93 @@ Deserialization is implemented with [Serde][3], and requires `gateway` feature t
 
 [3]: https://serde.rs
 [`gateway`]: crate::gateway
+
+# Reading and writing Ruuvi Station CSV files
+
+CSV files exported by the Ruuvi Station app can be read into timestamped sensor values, and
+written back in the same layout, when `csv` feature is enabled. See [`station`] module for
+documentation.
+
+[`station`]: crate::station
+
+# Reading logged measurements over Bluetooth
+
+Logged measurements can be read from a RuuviTag with firmware 3.x over Nordic UART Service. See
+[`nus`] module for the codec of the commands and responses.
+
+[`nus`]: crate::nus
+
+# Scanning with btleplug
+
+Advertisements found by [btleplug][4] scans can be decoded from the properties of a peripheral
+when `btleplug` feature is enabled. See [`btleplug`] module for documentation.
+
+[4]: https://docs.rs/btleplug
+[`btleplug`]: crate::btleplug
+
+# Decoding BlueZ device properties
+
+Advertisements received by BlueZ can be decoded from the D-Bus properties of a device when
+`bluez` feature is enabled. See [`bluez`] module for documentation.
+
+[`bluez`]: crate::bluez
+
+# Parsing HCI advertising reports
+
+Advertisements captured as raw HCI LE Advertising Report and LE Extended Advertising Report events
+can be parsed and decoded with [`hci`] module.
+
+[`hci`]: crate::hci
 */
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(feature = "gateway")]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "alloc")]
+pub use crate::calibration::{CalibrationTable, InvalidMacAddress};
+#[cfg(feature = "v8")]
+pub use crate::formats::EncryptionKey;
 pub use crate::{
+    calibration::{Calibrated, Calibration, Correction},
+    decode::{decode, DecodeError, DecodeInput, Decoded, ScanResult, Transport},
     errors::ParseError,
     formats::{
-        Acceleration, AccelerationVector, BatteryPotential, Humidity, MacAddress,
-        MeasurementSequenceNumber, MovementCounter, Pressure, SensorValues, Temperature,
-        TransmitterPower,
+        Acceleration, AccelerationVector, BatteryPotential, DataFormat, Field, FieldMetadata,
+        Fields, Humidity, Leniency, MacAddress, MeasurementSequenceNumber, MovementCounter,
+        ParseMode, Parsed, Parser, Pressure, SensorValues, Temperature, TransmitterPower, Unit,
+        Value,
     },
+    mac::{Mac, ParseMacError, SeparatedMac},
+    reading::Reading,
+    validation::{ImplausibleValue, PlausibilityLimits, PlausibilityReport, PressureLimits},
 };
 
+mod advertisement;
+#[cfg(feature = "alloc")]
+pub mod aggregation;
+#[cfg(feature = "alloc")]
+pub mod alerts;
+#[cfg(feature = "bluez")]
+pub mod bluez;
+#[cfg(feature = "btleplug")]
+pub mod btleplug;
+mod calibration;
+#[cfg(any(feature = "csv", feature = "gateway"))]
+mod decimal;
+mod decode;
 mod errors;
 mod formats;
 #[cfg(feature = "gateway")]
 pub mod gateway;
+pub mod hci;
+mod mac;
+pub mod nus;
+mod reading;
+#[cfg(feature = "csv")]
+pub mod station;
+mod validation;
 
 #[cfg(test)]
 mod testing;
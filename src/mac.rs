@@ -0,0 +1,336 @@
+use core::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// MAC address of a tag or a gateway
+///
+/// Addresses are parsed from hex digits separated by colons or dashes, or from 12 hex digits
+/// without separators, in either case. [`Display`] formats the address in the colon-separated
+/// uppercase form used by Ruuvi Gateway and Ruuvi Cloud, [`LowerHex`](fmt::LowerHex) and
+/// [`UpperHex`](fmt::UpperHex) without separators, and [`Mac::separated`] with any separator.
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::Mac;
+/// # use ruuvi_sensor_protocol::ParseMacError;
+///
+/// let mac: Mac = "c8-25-2d-8e-9c-2c".parse()?;
+///
+/// assert_eq!(mac, Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]));
+/// assert_eq!(mac.to_string(), "C8:25:2D:8E:9C:2C");
+/// assert_eq!(format!("{mac:x}"), "c8252d8e9c2c");
+/// assert_eq!(mac.separated('-').to_string(), "C8-25-2D-8E-9C-2C");
+/// # Ok::<(), ParseMacError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Mac([u8; 6]);
+
+impl Mac {
+    /// Returns an address from its octets
+    #[must_use]
+    pub const fn new(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+
+    /// Returns the octets of the address
+    #[must_use]
+    pub const fn octets(self) -> [u8; 6] {
+        self.0
+    }
+
+    /// Returns a value which formats the address as uppercase hex digits separated by the given
+    /// separator
+    #[must_use]
+    pub const fn separated(self, separator: char) -> SeparatedMac {
+        SeparatedMac {
+            mac: self,
+            separator,
+        }
+    }
+}
+
+impl From<[u8; 6]> for Mac {
+    fn from(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+}
+
+impl From<Mac> for [u8; 6] {
+    fn from(mac: Mac) -> Self {
+        mac.0
+    }
+}
+
+impl Display for Mac {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.separated(':'), formatter)
+    }
+}
+
+impl fmt::LowerHex for Mac {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+            .iter()
+            .try_for_each(|octet| write!(formatter, "{octet:02x}"))
+    }
+}
+
+impl fmt::UpperHex for Mac {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+            .iter()
+            .try_for_each(|octet| write!(formatter, "{octet:02X}"))
+    }
+}
+
+/// MAC address formatted with a separator, see [`Mac::separated`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SeparatedMac {
+    mac: Mac,
+    separator: char,
+}
+
+impl Display for SeparatedMac {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, octet) in self.mac.0.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, "{}", self.separator)?;
+            }
+            write!(formatter, "{octet:02X}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Error returned when a string is not a valid MAC address
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ParseMacError;
+
+impl Display for ParseMacError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "Invalid MAC address, expected 6 hex octets separated by colons, dashes or nothing"
+        )
+    }
+}
+
+impl core::error::Error for ParseMacError {}
+
+impl FromStr for Mac {
+    type Err = ParseMacError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_mac_address(value).map(Self).ok_or(ParseMacError)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Mac {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Mac {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Mac;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a MAC address")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value
+                    .parse()
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+/// Parses a MAC address from hex digits separated by colons or dashes, or from 12 hex digits
+/// without separators
+fn parse_mac_address(value: &str) -> Option<[u8; 6]> {
+    let mut address = [0; 6];
+    let separator = match value.len() {
+        12 => None,
+        17 => value.chars().nth(2).filter(|c| *c == ':' || *c == '-'),
+        _ => return None,
+    };
+
+    let mut digits = value.split(|c| Some(c) == separator);
+    let mut next_octet = |index: usize| match separator {
+        Some(_) => digits.next(),
+        None => value.get(index * 2..index * 2 + 2),
+    };
+
+    for (index, octet) in address.iter_mut().enumerate() {
+        let digits = next_octet(index).filter(|digits| {
+            digits.len() == 2 && digits.bytes().all(|digit| digit.is_ascii_hexdigit())
+        })?;
+        *octet = u8::from_str_radix(digits, 16).ok()?;
+    }
+
+    if separator.is_some() && digits.next().is_some() {
+        None
+    } else {
+        Some(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: Mac = Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]);
+
+    #[test]
+    fn mac_has_default_traits() {
+        crate::testing::type_has_default_traits::<Mac>();
+        crate::testing::type_has_default_traits::<SeparatedMac>();
+        crate::testing::type_has_default_traits::<ParseMacError>();
+    }
+
+    macro_rules! test_parse_mac_address {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(parse_mac_address($input), $result);
+                    assert_eq!($input.parse::<Mac>().ok().map(Mac::octets), $result);
+                }
+            )+
+        };
+    }
+
+    test_parse_mac_address! {
+        test colons {
+            input: "C8:25:2D:8E:9C:2C",
+            result: Some([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]),
+        }
+
+        test dashes {
+            input: "c8-25-2d-8e-9c-2c",
+            result: Some([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]),
+        }
+
+        test no_separators {
+            input: "c8252D8E9c2c",
+            result: Some([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]),
+        }
+
+        test mixed_separators {
+            input: "C8:25-2D:8E:9C:2C",
+            result: None,
+        }
+
+        test invalid_digits {
+            input: "C8:25:2D:8E:9C:2G",
+            result: None,
+        }
+
+        test signed_digits {
+            input: "+8252D8E9C2C",
+            result: None,
+        }
+
+        test too_short {
+            input: "C8:25:2D:8E:9C",
+            result: None,
+        }
+
+        test misplaced_separators {
+            input: "C8:252:D8:E9:C2:C",
+            result: None,
+        }
+
+        test empty {
+            input: "",
+            result: None,
+        }
+    }
+
+    #[test]
+    fn ordering_follows_octets() {
+        assert!(Mac::new([0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]) < MAC);
+        assert!(MAC < Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2D]));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Mac::from(MAC.octets()), MAC);
+        assert_eq!(<[u8; 6]>::from(MAC), MAC.octets());
+    }
+
+    #[cfg(feature = "alloc")]
+    mod formatting {
+        use alloc::{format, string::ToString};
+
+        use super::*;
+
+        #[test]
+        fn display() {
+            assert_eq!(MAC.to_string(), "C8:25:2D:8E:9C:2C");
+        }
+
+        #[test]
+        fn hex() {
+            assert_eq!(format!("{MAC:x}"), "c8252d8e9c2c");
+            assert_eq!(format!("{MAC:X}"), "C8252D8E9C2C");
+        }
+
+        #[test]
+        fn separated() {
+            assert_eq!(MAC.separated('-').to_string(), "C8-25-2D-8E-9C-2C");
+        }
+
+        #[test]
+        fn display_round_trip() {
+            assert_eq!(MAC.to_string().parse(), Ok(MAC));
+            assert_eq!(format!("{MAC:x}").parse(), Ok(MAC));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::*;
+
+        #[test]
+        fn serialize() {
+            assert_eq!(
+                serde_json::to_string(&MAC).ok().as_deref(),
+                Some("\"C8:25:2D:8E:9C:2C\"")
+            );
+        }
+
+        #[test]
+        fn deserialize() {
+            assert_eq!(
+                serde_json::from_str::<Mac>("\"c8252d8e9c2c\"").ok(),
+                Some(MAC)
+            );
+        }
+
+        #[test]
+        fn deserialize_invalid() {
+            assert_eq!(serde_json::from_str::<Mac>("\"C8:25:2D\"").ok(), None);
+        }
+    }
+}
@@ -0,0 +1,361 @@
+/*!
+
+This module implements the binary protocol used by RuuviTag firmware 3.x over Nordic UART Service
+(NUS) for reading logged measurements.
+
+The codec does not depend on any Bluetooth library: [`LogReadCommand`] builds the bytes written to
+the RX characteristic, and [`LogResponse::parse`] decodes each notification received from the TX
+characteristic. Every record contains a single temperature, humidity or pressure value, and the
+tag ends the log with a separate end-of-log marker.
+
+While connected, the tag also sends its current measurements as notifications, which can be parsed
+with [`SensorValues::from_nus_notification`].
+
+[`SensorValues::from_nus_notification`]: crate::SensorValues::from_nus_notification
+
+```rust
+use ruuvi_sensor_protocol::nus::{LogReadCommand, LogRecord, LogResponse, LogValue};
+# use ruuvi_sensor_protocol::nus::LogFrameError;
+
+let command = LogReadCommand::new(1_653_668_027, 1_653_063_227);
+assert_eq!(
+    command.to_bytes(),
+    [0x3A, 0x3A, 0x11, 0x62, 0x90, 0xF8, 0xBB, 0x62, 0x87, 0xBE, 0x3B],
+);
+
+let record = [0x3A, 0x30, 0x10, 0x62, 0x90, 0xF8, 0xBB, 0x00, 0x00, 0x08, 0xCA];
+assert_eq!(
+    LogResponse::parse(&record)?,
+    LogResponse::Record(LogRecord {
+        timestamp: 1_653_668_027,
+        value: LogValue::Temperature(22_500),
+    }),
+);
+
+let end = [0x3A, 0x3A, 0x10, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+assert_eq!(LogResponse::parse(&end)?, LogResponse::End);
+# Ok::<(), LogFrameError>(())
+```
+
+*/
+use core::fmt::{self, Display};
+
+/// Endpoint of all environmental measurements
+const ENVIRONMENTAL: u8 = 0x3A;
+/// Endpoint of temperature measurements
+const TEMPERATURE: u8 = 0x30;
+/// Endpoint of humidity measurements
+const HUMIDITY: u8 = 0x31;
+/// Endpoint of pressure measurements
+const PRESSURE: u8 = 0x32;
+/// Operation of a logged value sent by the tag
+const LOG_VALUE_WRITE: u8 = 0x10;
+/// Operation of a log read request sent to the tag
+const LOG_VALUE_READ: u8 = 0x11;
+
+/// Length of commands and responses in bytes
+pub const FRAME_LENGTH: usize = 11;
+
+/// Command requesting the tag to send logged measurements
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LogReadCommand {
+    /// current time as seconds since Unix epoch, used by the tag to convert its clock
+    pub now: u32,
+    /// time of the oldest measurement to send as seconds since Unix epoch
+    pub start: u32,
+}
+
+impl LogReadCommand {
+    /// Returns a command reading measurements logged since `start`
+    #[must_use]
+    pub fn new(now: u32, start: u32) -> Self {
+        Self { now, start }
+    }
+
+    /// Returns the bytes of the command to be written to the RX characteristic
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; FRAME_LENGTH] {
+        let [now_1, now_2, now_3, now_4] = self.now.to_be_bytes();
+        let [start_1, start_2, start_3, start_4] = self.start.to_be_bytes();
+
+        [
+            ENVIRONMENTAL,
+            ENVIRONMENTAL,
+            LOG_VALUE_READ,
+            now_1,
+            now_2,
+            now_3,
+            now_4,
+            start_1,
+            start_2,
+            start_3,
+            start_4,
+        ]
+    }
+}
+
+/// Response frame sent by the tag while reading the log
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LogResponse {
+    /// Logged measurement
+    Record(LogRecord),
+    /// Marker sent after the last record
+    End,
+}
+
+/// Logged measurement
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LogRecord {
+    /// time of the measurement as seconds since Unix epoch
+    pub timestamp: u32,
+    /// measured value
+    pub value: LogValue,
+}
+
+/// Value of a logged measurement, in the same units as measurement traits of this crate
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LogValue {
+    /// temperature in millicelsius
+    Temperature(i32),
+    /// relative humidity in parts per million
+    Humidity(u32),
+    /// pressure in pascals
+    Pressure(u32),
+}
+
+impl LogResponse {
+    /// Parses a response frame received from the TX characteristic.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame has a wrong length, is not a logged value, or contains a
+    /// value out of range.
+    pub fn parse(frame: &[u8]) -> Result<Self, LogFrameError> {
+        let frame: &[u8; FRAME_LENGTH] = frame
+            .try_into()
+            .map_err(|_| LogFrameError::InvalidLength(frame.len()))?;
+        let [destination, source, operation, ..] = *frame;
+        let timestamp = u32::from_be_bytes([frame[3], frame[4], frame[5], frame[6]]);
+        let value = i32::from_be_bytes([frame[7], frame[8], frame[9], frame[10]]);
+
+        let record = |value| Ok(Self::Record(LogRecord { timestamp, value }));
+        let out_of_range = LogFrameError::ValueOutOfRange(source);
+
+        match (destination, source, operation) {
+            (ENVIRONMENTAL, ENVIRONMENTAL, LOG_VALUE_WRITE) if frame[3..] == [0xFF; 8] => {
+                Ok(Self::End)
+            }
+            (ENVIRONMENTAL, TEMPERATURE, LOG_VALUE_WRITE) => record(LogValue::Temperature(
+                value.checked_mul(10).ok_or(out_of_range)?,
+            )),
+            (ENVIRONMENTAL, HUMIDITY, LOG_VALUE_WRITE) => record(LogValue::Humidity(
+                u32::try_from(value)
+                    .ok()
+                    .and_then(|value| value.checked_mul(100))
+                    .ok_or(out_of_range)?,
+            )),
+            (ENVIRONMENTAL, PRESSURE, LOG_VALUE_WRITE) => record(LogValue::Pressure(
+                u32::try_from(value).map_err(|_| out_of_range)?,
+            )),
+            _ => Err(LogFrameError::UnknownHeader([
+                destination,
+                source,
+                operation,
+            ])),
+        }
+    }
+}
+
+/// Errors which can occur when parsing a log response frame
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LogFrameError {
+    /// Frame is not 11 bytes long
+    InvalidLength(usize),
+    /// Destination, source and operation of the frame do not match a logged value
+    UnknownHeader([u8; 3]),
+    /// Value of the measurement from the given source can not be represented
+    ValueOutOfRange(u8),
+}
+
+impl Display for LogFrameError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            LogFrameError::InvalidLength(length) => write!(
+                formatter,
+                "Invalid log frame length of {length}, expected {FRAME_LENGTH}"
+            ),
+            LogFrameError::UnknownHeader([destination, source, operation]) => write!(
+                formatter,
+                "Unknown log frame header {destination:#04X} {source:#04X} {operation:#04X}"
+            ),
+            LogFrameError::ValueOutOfRange(source) => {
+                write!(
+                    formatter,
+                    "Value out of range in log frame from {source:#04X}"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for LogFrameError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_types_have_default_traits() {
+        crate::testing::type_has_default_traits::<LogReadCommand>();
+        crate::testing::type_has_default_traits::<LogResponse>();
+        crate::testing::type_has_default_traits::<LogRecord>();
+        crate::testing::type_has_default_traits::<LogValue>();
+        crate::testing::type_has_default_traits::<LogFrameError>();
+    }
+
+    #[test]
+    fn log_read_command() {
+        let command = LogReadCommand::new(1_653_668_027, 0);
+
+        assert_eq!(
+            command.to_bytes(),
+            [0x3A, 0x3A, 0x11, 0x62, 0x90, 0xF8, 0xBB, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    macro_rules! test_parse_response {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(LogResponse::parse(&$input), $result);
+                }
+            )+
+        };
+    }
+
+    const TIMESTAMP: u32 = 1_653_667_727;
+
+    test_parse_response! {
+        test temperature_record {
+            input: [0x3A, 0x30, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0x00, 0x00, 0x08, 0xCA],
+            result: Ok(LogResponse::Record(LogRecord {
+                timestamp: TIMESTAMP,
+                value: LogValue::Temperature(22_500),
+            })),
+        }
+
+        test negative_temperature_record {
+            input: [0x3A, 0x30, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0xFF, 0xFF, 0xFB, 0x2E],
+            result: Ok(LogResponse::Record(LogRecord {
+                timestamp: TIMESTAMP,
+                value: LogValue::Temperature(-12_340),
+            })),
+        }
+
+        test humidity_record {
+            input: [0x3A, 0x31, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0x00, 0x00, 0x11, 0xA0],
+            result: Ok(LogResponse::Record(LogRecord {
+                timestamp: TIMESTAMP,
+                value: LogValue::Humidity(451_200),
+            })),
+        }
+
+        test pressure_record {
+            input: [0x3A, 0x32, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0x00, 0x01, 0x8A, 0x2E],
+            result: Ok(LogResponse::Record(LogRecord {
+                timestamp: TIMESTAMP,
+                value: LogValue::Pressure(100_910),
+            })),
+        }
+
+        test end_of_log {
+            input: [0x3A, 0x3A, 0x10, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            result: Ok(LogResponse::End),
+        }
+
+        test negative_humidity {
+            input: [0x3A, 0x31, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0xFF, 0xFF, 0xFB, 0x2E],
+            result: Err(LogFrameError::ValueOutOfRange(0x31)),
+        }
+
+        test environmental_source_without_end_marker {
+            input: [0x3A, 0x3A, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0x00, 0x01, 0x8A, 0x2E],
+            result: Err(LogFrameError::UnknownHeader([0x3A, 0x3A, 0x10])),
+        }
+
+        test read_command_echo {
+            input: [0x3A, 0x3A, 0x11, 0x62, 0x90, 0xF8, 0xBB, 0x00, 0x00, 0x00, 0x00],
+            result: Err(LogFrameError::UnknownHeader([0x3A, 0x3A, 0x11])),
+        }
+
+        test short_frame {
+            input: [0x3A, 0x30, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0x00, 0x00, 0x08],
+            result: Err(LogFrameError::InvalidLength(10)),
+        }
+
+        test empty_frame {
+            input: [],
+            result: Err(LogFrameError::InvalidLength(0)),
+        }
+    }
+
+    #[test]
+    fn constructed_log_sequence() {
+        // Hand-constructed notifications following the documented frame layout, not captured from
+        // a tag: two measurement rounds followed by the end-of-log marker
+        const FRAMES: [[u8; FRAME_LENGTH]; 7] = [
+            [
+                0x3A, 0x30, 0x10, 0x62, 0x87, 0xBE, 0x3B, 0x00, 0x00, 0x08, 0xCA,
+            ],
+            [
+                0x3A, 0x31, 0x10, 0x62, 0x87, 0xBE, 0x3B, 0x00, 0x00, 0x11, 0xA0,
+            ],
+            [
+                0x3A, 0x32, 0x10, 0x62, 0x87, 0xBE, 0x3B, 0x00, 0x01, 0x8A, 0x2E,
+            ],
+            [
+                0x3A, 0x30, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0xFF, 0xFF, 0xFB, 0x2E,
+            ],
+            [
+                0x3A, 0x31, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0x00, 0x00, 0x11, 0xA0,
+            ],
+            [
+                0x3A, 0x32, 0x10, 0x62, 0x90, 0xF7, 0x8F, 0x00, 0x01, 0x8A, 0x2E,
+            ],
+            [
+                0x3A, 0x3A, 0x10, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            ],
+        ];
+
+        let responses = FRAMES.map(|frame| LogResponse::parse(&frame));
+        let (records, end) = responses.split_at(6);
+        let timestamps: [u32; 6] = core::array::from_fn(|index| match records[index] {
+            Ok(LogResponse::Record(record)) => record.timestamp,
+            _ => 0,
+        });
+
+        assert_eq!(end, [Ok(LogResponse::End)]);
+        assert_eq!(
+            timestamps,
+            [
+                1_653_063_227,
+                1_653_063_227,
+                1_653_063_227,
+                TIMESTAMP,
+                TIMESTAMP,
+                TIMESTAMP
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+/// Values received from a tag at a point in time
+///
+/// The timestamp is a Unix timestamp in seconds. Signal strength is available only when the
+/// values were received over Bluetooth by a known receiver.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reading<T> {
+    /// time of the reading as seconds since Unix epoch
+    pub timestamp: u64,
+    /// received signal strength in dBm, if known
+    pub rssi: Option<i16>,
+    /// values received from the tag
+    pub values: T,
+}
+
+impl<T> Reading<T> {
+    /// Returns a reading without signal strength information
+    pub fn new(timestamp: u64, values: T) -> Self {
+        Self {
+            timestamp,
+            rssi: None,
+            values,
+        }
+    }
+
+    /// Returns a reading with the given signal strength
+    #[must_use]
+    pub fn with_rssi(self, rssi: i16) -> Self {
+        Self {
+            rssi: Some(rssi),
+            ..self
+        }
+    }
+
+    /// Converts the values of the reading while keeping the timestamp and signal strength
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Reading<U> {
+        Reading {
+            timestamp: self.timestamp,
+            rssi: self.rssi,
+            values: f(self.values),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_has_default_traits() {
+        crate::testing::type_has_default_traits::<Reading<crate::SensorValues>>();
+    }
+
+    #[test]
+    fn map_keeps_metadata() {
+        let reading = Reading::new(1_653_668_027, 2)
+            .with_rssi(-25)
+            .map(|value| value * 2);
+
+        assert_eq!(
+            reading,
+            Reading {
+                timestamp: 1_653_668_027,
+                rssi: Some(-25),
+                values: 4,
+            }
+        );
+    }
+}
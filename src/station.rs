@@ -0,0 +1,801 @@
+/*!
+
+This module reads and writes CSV files in the layout exported by the Ruuvi Station app.
+
+Each row of the file contains a date and measurements of a tag in the units used by Ruuvi Station.
+When reading, columns are matched by the header, so their order does not matter, unknown columns
+are ignored, and missing columns or empty cells are read as unavailable values. The RSSI column is
+read into the signal strength of the reading. Dates are interpreted as UTC.
+
+```rust
+use ruuvi_sensor_protocol::{station, Humidity, Temperature};
+# use ruuvi_sensor_protocol::station::CsvError;
+
+let input = "\
+Date,Temperature (°C),Humidity (%),Pressure (hPa)
+2022-05-27 16:13:47,24.30,53.49,1000.44
+";
+
+let readings = station::read_csv(input)?;
+
+assert_eq!(readings[0].timestamp, 1_653_668_027);
+assert_eq!(readings[0].values.temperature_as_millicelsius(), Some(24_300));
+assert_eq!(readings[0].values.humidity_as_ppm(), Some(534_900));
+
+let mut output = String::new();
+station::write_csv(&mut output, &readings).unwrap();
+
+assert!(output.starts_with("Date,Temperature (°C)"));
+# Ok::<(), CsvError>(())
+```
+
+*/
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt::{self, Display, Write};
+
+use crate::{
+    decimal::scale, Acceleration, AccelerationVector, BatteryPotential, Humidity, Mac, MacAddress,
+    MeasurementSequenceNumber, MovementCounter, Pressure, Reading, SensorValues, Temperature,
+    TransmitterPower,
+};
+
+/// Errors which can occur when reading a CSV file
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CsvError {
+    /// Input does not contain a header row
+    MissingHeader,
+    /// Header does not contain a date column
+    MissingDateColumn,
+    /// Header of the column has a unit that is not supported, columns are numbered from 1
+    UnsupportedUnit {
+        /// number of the column
+        column: usize,
+    },
+    /// Cell does not contain a valid value, lines and columns are numbered from 1
+    InvalidValue {
+        /// number of the line
+        line: usize,
+        /// number of the column
+        column: usize,
+    },
+}
+
+impl Display for CsvError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            CsvError::MissingHeader => write!(formatter, "CSV file does not contain a header"),
+            CsvError::MissingDateColumn => write!(formatter, "CSV file does not contain dates"),
+            CsvError::UnsupportedUnit { column } => {
+                write!(formatter, "Unsupported unit in header of column {column}")
+            }
+            CsvError::InvalidValue { line, column } => {
+                write!(formatter, "Invalid value on line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for CsvError {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Column {
+    Date,
+    Temperature,
+    Humidity,
+    Pressure,
+    Rssi,
+    AccelerationX,
+    AccelerationY,
+    AccelerationZ,
+    Voltage,
+    MovementCounter,
+    MeasurementSequenceNumber,
+    TxPower,
+}
+
+impl Column {
+    const ALL: [Self; 12] = [
+        Self::Date,
+        Self::Temperature,
+        Self::Humidity,
+        Self::Pressure,
+        Self::Rssi,
+        Self::AccelerationX,
+        Self::AccelerationY,
+        Self::AccelerationZ,
+        Self::Voltage,
+        Self::MovementCounter,
+        Self::MeasurementSequenceNumber,
+        Self::TxPower,
+    ];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Date => "Date",
+            Self::Temperature => "Temperature",
+            Self::Humidity => "Humidity",
+            Self::Pressure => "Pressure",
+            Self::Rssi => "RSSI",
+            Self::AccelerationX => "Acceleration X",
+            Self::AccelerationY => "Acceleration Y",
+            Self::AccelerationZ => "Acceleration Z",
+            Self::Voltage => "Voltage",
+            Self::MovementCounter => "Movement counter",
+            Self::MeasurementSequenceNumber => "Measurement sequence number",
+            Self::TxPower => "TX power",
+        }
+    }
+
+    const fn unit(self) -> Option<&'static str> {
+        match self {
+            Self::Date | Self::MovementCounter | Self::MeasurementSequenceNumber => None,
+            Self::Temperature => Some("°C"),
+            Self::Humidity => Some("%"),
+            Self::Pressure => Some("hPa"),
+            Self::AccelerationX | Self::AccelerationY | Self::AccelerationZ => Some("g"),
+            Self::Voltage => Some("V"),
+            Self::Rssi | Self::TxPower => Some("dBm"),
+        }
+    }
+
+    /// Matches a header cell like `Pressure (hPa)` to a column. Returns `Ok(None)` for unknown
+    /// columns and `Err(())` for known columns with an unsupported unit.
+    fn from_header(header: &str) -> Result<Option<Self>, ()> {
+        let (name, unit) = match header.split_once('(') {
+            Some((name, unit)) => (name.trim(), Some(unit.trim_end_matches(')').trim())),
+            None => (header, None),
+        };
+        let column = Self::ALL.into_iter().find(|column| {
+            column.name().eq_ignore_ascii_case(name)
+                || (*column == Self::Date && name.eq_ignore_ascii_case("Timestamp"))
+        });
+
+        match (column, unit) {
+            (Some(column), Some(unit)) if column.unit() != Some(unit) => Err(()),
+            (column, _) => Ok(column),
+        }
+    }
+}
+
+/// Values of a single row, in the units of [`SensorValues`] and [`Reading`]
+#[derive(Default)]
+struct Row {
+    rssi: Option<i16>,
+    temperature: Option<i32>,
+    humidity: Option<u32>,
+    pressure: Option<u32>,
+    acceleration: [Option<i16>; 3],
+    battery_potential: Option<u16>,
+    movement_counter: Option<u32>,
+    measurement_sequence_number: Option<u32>,
+    tx_power: Option<i8>,
+}
+
+impl Row {
+    fn set(&mut self, column: Column, cell: &str) -> Option<()> {
+        let decimal = || cell.parse::<f64>().ok();
+
+        match column {
+            Column::Date => {}
+            Column::Temperature => self.temperature = Some(scale(decimal()?, 1000.0)?),
+            Column::Humidity => self.humidity = Some(scale(decimal()?, 10_000.0)?),
+            Column::Pressure => self.pressure = Some(scale(decimal()?, 100.0)?),
+            Column::Rssi => self.rssi = Some(cell.parse().ok()?),
+            Column::AccelerationX => self.acceleration[0] = Some(scale(decimal()?, 1000.0)?),
+            Column::AccelerationY => self.acceleration[1] = Some(scale(decimal()?, 1000.0)?),
+            Column::AccelerationZ => self.acceleration[2] = Some(scale(decimal()?, 1000.0)?),
+            Column::Voltage => self.battery_potential = Some(scale(decimal()?, 1000.0)?),
+            Column::MovementCounter => self.movement_counter = Some(cell.parse().ok()?),
+            Column::MeasurementSequenceNumber => {
+                self.measurement_sequence_number = Some(cell.parse().ok()?);
+            }
+            Column::TxPower => self.tx_power = Some(cell.parse().ok()?),
+        }
+
+        Some(())
+    }
+}
+
+impl Acceleration for Row {
+    fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
+        let [x, y, z] = self.acceleration;
+        Some(AccelerationVector(x?, y?, z?))
+    }
+}
+
+impl BatteryPotential for Row {
+    fn battery_potential_as_millivolts(&self) -> Option<u16> {
+        self.battery_potential
+    }
+}
+
+impl Humidity for Row {
+    fn humidity_as_ppm(&self) -> Option<u32> {
+        self.humidity
+    }
+}
+
+impl MacAddress for Row {
+    fn mac_address(&self) -> Option<Mac> {
+        None
+    }
+}
+
+impl MeasurementSequenceNumber for Row {
+    fn measurement_sequence_number(&self) -> Option<u32> {
+        self.measurement_sequence_number
+    }
+}
+
+impl MovementCounter for Row {
+    fn movement_counter(&self) -> Option<u32> {
+        self.movement_counter
+    }
+}
+
+impl Pressure for Row {
+    fn pressure_as_pascals(&self) -> Option<u32> {
+        self.pressure
+    }
+}
+
+impl Temperature for Row {
+    fn temperature_as_millikelvins(&self) -> Option<u32> {
+        let temperature = i64::from(self.temperature?);
+        u32::try_from(temperature + i64::from(Self::ZERO_CELSIUS_IN_MILLIKELVINS)).ok()
+    }
+}
+
+impl TransmitterPower for Row {
+    fn tx_power_as_dbm(&self) -> Option<i8> {
+        self.tx_power
+    }
+}
+
+/// Splits a line into cells, keeping commas inside quoted cells and unescaping doubled quotes
+fn cells(line: &str) -> impl Iterator<Item = Cow<'_, str>> {
+    let mut rest = Some(line);
+
+    core::iter::from_fn(move || {
+        let line = rest?.trim_start();
+
+        let Some(quoted) = line.strip_prefix('"') else {
+            let (cell, next) = match line.split_once(',') {
+                Some((cell, next)) => (cell, Some(next)),
+                None => (line, None),
+            };
+            rest = next;
+            return Some(Cow::Borrowed(cell.trim()));
+        };
+
+        let (cell, after) = unquote(quoted);
+        rest = after.split_once(',').map(|(_, next)| next);
+        Some(cell)
+    })
+}
+
+/// Reads a quoted cell up to its closing quote, returning the cell and the rest of the line
+fn unquote(quoted: &str) -> (Cow<'_, str>, &str) {
+    let mut cell = Cow::Borrowed("");
+    let mut remaining = quoted;
+
+    while let Some((text, after)) = remaining.split_once('"') {
+        if let Some(after) = after.strip_prefix('"') {
+            cell.to_mut().push_str(text);
+            cell.to_mut().push('"');
+            remaining = after;
+        } else {
+            if cell.is_empty() {
+                cell = Cow::Borrowed(text);
+            } else {
+                cell.to_mut().push_str(text);
+            }
+            return (cell, after);
+        }
+    }
+
+    // Cell without a closing quote extends to the end of the line
+    cell.to_mut().push_str(remaining);
+    (cell, "")
+}
+
+/// Reads timestamped sensor values from a CSV file exported by Ruuvi Station.
+///
+/// # Errors
+///
+/// Returns an error if the input has no header or date column, a column has an unsupported unit,
+/// or a cell contains an invalid value.
+pub fn read_csv(input: &str) -> Result<Vec<Reading<SensorValues>>, CsvError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines.next().ok_or(CsvError::MissingHeader)?;
+    let columns = cells(header.trim_start_matches('\u{FEFF}'))
+        .enumerate()
+        .map(|(index, header)| {
+            Column::from_header(&header)
+                .map_err(|()| CsvError::UnsupportedUnit { column: index + 1 })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !columns.contains(&Some(Column::Date)) {
+        return Err(CsvError::MissingDateColumn);
+    }
+
+    lines
+        .map(|(index, line)| {
+            let mut timestamp = None;
+            let mut row = Row::default();
+
+            for (column_index, (column, cell)) in columns.iter().zip(cells(line)).enumerate() {
+                let invalid = CsvError::InvalidValue {
+                    line: index + 1,
+                    column: column_index + 1,
+                };
+
+                match column {
+                    Some(Column::Date) => timestamp = Some(parse_date(&cell).ok_or(invalid)?),
+                    Some(column) if !cell.is_empty() => row.set(*column, &cell).ok_or(invalid)?,
+                    _ => {}
+                }
+            }
+
+            let timestamp = timestamp.ok_or(CsvError::InvalidValue {
+                line: index + 1,
+                column: columns
+                    .iter()
+                    .position(|c| *c == Some(Column::Date))
+                    .unwrap_or(0)
+                    + 1,
+            })?;
+
+            Ok(Reading {
+                timestamp,
+                rssi: row.rssi,
+                values: SensorValues::from_measurements(&row),
+            })
+        })
+        .collect()
+}
+
+/// Writes timestamped sensor values as a CSV file in the layout exported by Ruuvi Station.
+/// Unavailable values and signal strengths are written as empty cells and dates are written in
+/// UTC.
+///
+/// # Errors
+///
+/// Returns an error if writing to the output fails.
+pub fn write_csv<'a, W, T>(
+    output: &mut W,
+    readings: impl IntoIterator<Item = &'a Reading<T>>,
+) -> fmt::Result
+where
+    W: Write,
+    T: Acceleration
+        + BatteryPotential
+        + Humidity
+        + MeasurementSequenceNumber
+        + MovementCounter
+        + Pressure
+        + Temperature
+        + TransmitterPower
+        + 'a,
+{
+    for (index, column) in Column::ALL.into_iter().enumerate() {
+        if index > 0 {
+            output.write_char(',')?;
+        }
+        output.write_str(column.name())?;
+        if let Some(unit) = column.unit() {
+            write!(output, " ({unit})")?;
+        }
+    }
+    output.write_char('\n')?;
+
+    for reading in readings {
+        let values = &reading.values;
+        let acceleration = values
+            .acceleration_vector_as_milli_g()
+            .map(|AccelerationVector(x, y, z)| [x, y, z]);
+        let acceleration = |index: usize| acceleration.map(|vector| vector[index]);
+
+        write_date(output, reading.timestamp)?;
+        write_decimal(output, values.temperature_as_millicelsius(), 3)?;
+        write_decimal(output, values.humidity_as_ppm(), 4)?;
+        write_decimal(output, values.pressure_as_pascals(), 2)?;
+        write_decimal(output, reading.rssi, 0)?;
+        write_decimal(output, acceleration(0), 3)?;
+        write_decimal(output, acceleration(1), 3)?;
+        write_decimal(output, acceleration(2), 3)?;
+        write_decimal(output, values.battery_potential_as_millivolts(), 3)?;
+        write_decimal(output, values.movement_counter(), 0)?;
+        write_decimal(output, values.measurement_sequence_number(), 0)?;
+        write_decimal(output, values.tx_power_as_dbm(), 0)?;
+        output.write_char('\n')?;
+    }
+
+    Ok(())
+}
+
+/// Writes a comma and an integer value with a decimal point inserted before the given number of
+/// digits
+fn write_decimal<W: Write>(
+    output: &mut W,
+    value: Option<impl Into<i64>>,
+    decimals: u32,
+) -> fmt::Result {
+    output.write_char(',')?;
+
+    if let Some(value) = value {
+        let value: i64 = value.into();
+        let divisor = 10_u64.pow(decimals);
+        let sign = if value < 0 { "-" } else { "" };
+        let (integer, fraction) = (
+            value.unsigned_abs() / divisor,
+            value.unsigned_abs() % divisor,
+        );
+
+        if decimals == 0 {
+            write!(output, "{sign}{integer}")?;
+        } else {
+            let width = usize::try_from(decimals).map_err(|_| fmt::Error)?;
+            write!(output, "{sign}{integer}.{fraction:0width$}")?;
+        }
+    }
+
+    Ok(())
+}
+
+const SECONDS_PER_DAY: u64 = 86_400;
+/// Number of days from 0000-03-01 to 1970-01-01
+const UNIX_EPOCH_DAYS: i64 = 719_468;
+
+fn write_date<W: Write>(output: &mut W, timestamp: u64) -> fmt::Result {
+    let days = i64::try_from(timestamp / SECONDS_PER_DAY).map_err(|_| fmt::Error)?;
+    let seconds = timestamp % SECONDS_PER_DAY;
+    let (year, month, day) = civil_from_days(days);
+
+    write!(
+        output,
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Parses a Unix timestamp or a date formatted as `YYYY-MM-DD HH:MM:SS`. The date may also be
+/// separated from the time with `T` and end with `Z`.
+fn parse_date(cell: &str) -> Option<u64> {
+    if cell.bytes().all(|byte| byte.is_ascii_digit()) {
+        return cell.parse().ok();
+    }
+
+    let cell = cell.strip_suffix('Z').unwrap_or(cell);
+    let (date, time) = cell.split_once([' ', 'T'])?;
+
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut time = time.splitn(3, ':').map(str::parse::<u64>);
+    let (hours, minutes, seconds) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    if hours >= 24 || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+
+    u64::try_from(days)
+        .ok()?
+        .checked_mul(SECONDS_PER_DAY)?
+        .checked_add(hours * 3600 + minutes * 60 + seconds)
+}
+
+/// Returns the number of days since Unix epoch for a date in the proleptic Gregorian calendar. The
+/// month must be between 1 and 12, and the year and the day small enough not to overflow.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - UNIX_EPOCH_DAYS
+}
+
+/// Returns the date in the proleptic Gregorian calendar for a number of days since Unix epoch
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + UNIX_EPOCH_DAYS;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[expect(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::*;
+
+    const EXPORT: &str = "\
+\u{FEFF}\"Date\",\"Temperature (°C)\",\"Humidity (%)\",\"Pressure (hPa)\",\"RSSI (dBm)\",\
+\"Acceleration X (g)\",\"Acceleration Y (g)\",\"Acceleration Z (g)\",\"Voltage (V)\",\
+\"Movement counter\",\"Measurement sequence number\",\"TX power (dBm)\"\r
+2022-05-27 16:13:47,28.66,54.9325,1009.10,-25,0.008,-0.012,1.008,2.995,15,10891,4\r
+2022-05-27 16:18:47,28.7,54.9,1009.12,,,,,,,,\r
+";
+
+    #[test]
+    fn csv_error_has_default_traits() {
+        crate::testing::type_has_default_traits::<CsvError>();
+    }
+
+    #[test]
+    fn read_station_export() {
+        let readings = read_csv(EXPORT).unwrap();
+
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[0].timestamp, 1_653_668_027);
+        assert_eq!(readings[0].rssi, Some(-25));
+
+        let values = &readings[0].values;
+        assert_eq!(values.temperature_as_millicelsius(), Some(28_660));
+        assert_eq!(values.humidity_as_ppm(), Some(549_325));
+        assert_eq!(values.pressure_as_pascals(), Some(100_910));
+        assert_eq!(
+            values.acceleration_vector_as_milli_g(),
+            Some(AccelerationVector(8, -12, 1008))
+        );
+        assert_eq!(values.battery_potential_as_millivolts(), Some(2995));
+        assert_eq!(values.movement_counter(), Some(15));
+        assert_eq!(values.measurement_sequence_number(), Some(10891));
+        assert_eq!(values.tx_power_as_dbm(), Some(4));
+        assert_eq!(values.mac_address(), None);
+
+        let values = &readings[1].values;
+        assert_eq!(readings[1].timestamp, 1_653_668_327);
+        assert_eq!(readings[1].rssi, None);
+        assert_eq!(values.temperature_as_millicelsius(), Some(28_700));
+        assert_eq!(values.acceleration_vector_as_milli_g(), None);
+        assert_eq!(values.movement_counter(), None);
+    }
+
+    #[test]
+    fn read_quoted_comma() {
+        let input = "\
+Date,Name,Temperature (°C)
+2022-05-27 16:13:47,\"Sauna, \"\"upper\"\" bench\",28.66
+";
+        let readings = read_csv(input).unwrap();
+
+        assert_eq!(readings.len(), 1);
+        assert_eq!(
+            readings[0].values.temperature_as_millicelsius(),
+            Some(28_660)
+        );
+    }
+
+    #[test]
+    fn split_quoted_cells() {
+        let cells = cells(" a ,\"b, \"\"c\"\"\",,\"d").collect::<Vec<_>>();
+
+        assert_eq!(cells, ["a", "b, \"c\"", "", "d"]);
+    }
+
+    #[test]
+    fn write_and_read() {
+        let readings = read_csv(EXPORT).unwrap();
+        let mut output = String::new();
+        write_csv(&mut output, &readings).unwrap();
+
+        assert_eq!(
+            output,
+            "\
+Date,Temperature (°C),Humidity (%),Pressure (hPa),RSSI (dBm),Acceleration X (g),\
+Acceleration Y (g),Acceleration Z (g),Voltage (V),Movement counter,Measurement sequence number,\
+TX power (dBm)
+2022-05-27 16:13:47,28.660,54.9325,1009.10,-25,0.008,-0.012,1.008,2.995,15,10891,4
+2022-05-27 16:18:47,28.700,54.9000,1009.12,,,,,,,,
+"
+        );
+        assert_eq!(read_csv(&output).unwrap(), readings);
+    }
+
+    #[test]
+    fn write_negative_values() {
+        let mut output = String::new();
+        write_decimal(&mut output, Some(-5_i32), 3).unwrap();
+        write_decimal(&mut output, Some(-12_345_i32), 3).unwrap();
+        write_decimal(&mut output, None::<i32>, 3).unwrap();
+
+        assert_eq!(output, ",-0.005,-12.345,");
+    }
+
+    macro_rules! test_read_csv_error {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(read_csv($input), Err($result));
+                }
+            )+
+        };
+    }
+
+    test_read_csv_error! {
+        test empty_input {
+            input: "\n",
+            result: CsvError::MissingHeader,
+        }
+
+        test no_date_column {
+            input: "Temperature (°C)\n24.3\n",
+            result: CsvError::MissingDateColumn,
+        }
+
+        test unsupported_unit {
+            input: "Date,Temperature (°F)\n2022-05-27 16:13:47,75.7\n",
+            result: CsvError::UnsupportedUnit { column: 2 },
+        }
+
+        test invalid_number {
+            input: "Date,Humidity (%)\n2022-05-27 16:13:47,53.49\n2022-05-27 16:18:47,high\n",
+            result: CsvError::InvalidValue { line: 3, column: 2 },
+        }
+
+        test invalid_date {
+            input: "Humidity (%),Date\n53.49,2022-02-30 16:13:47\n",
+            result: CsvError::InvalidValue { line: 2, column: 2 },
+        }
+
+        test missing_date {
+            input: "Humidity (%),Date\n53.49\n",
+            result: CsvError::InvalidValue { line: 2, column: 2 },
+        }
+
+        test out_of_range_value {
+            input: "Date,Voltage (V)\n2022-05-27 16:13:47,-3.0\n",
+            result: CsvError::InvalidValue { line: 2, column: 2 },
+        }
+
+        test invalid_rssi {
+            input: "Date,RSSI (dBm)\n2022-05-27 16:13:47,-25.5\n",
+            result: CsvError::InvalidValue { line: 2, column: 2 },
+        }
+
+        test overflowing_year {
+            input: "Date\n9999999999999999-01-01 00:00:00\n",
+            result: CsvError::InvalidValue { line: 2, column: 1 },
+        }
+
+        test overflowing_month {
+            input: "Date\n2022-9223372036854775807-01 00:00:00\n",
+            result: CsvError::InvalidValue { line: 2, column: 1 },
+        }
+    }
+
+    macro_rules! test_parse_date {
+        (
+            $(
+                test $name: ident {
+                    input: $input: expr,
+                    result: $result: expr,
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(parse_date($input), $result);
+                }
+            )+
+        };
+    }
+
+    test_parse_date! {
+        test unix_epoch {
+            input: "1970-01-01 00:00:00",
+            result: Some(0),
+        }
+
+        test unix_timestamp {
+            input: "1653668027",
+            result: Some(1_653_668_027),
+        }
+
+        test iso_8601 {
+            input: "2022-05-27T16:13:47Z",
+            result: Some(1_653_668_027),
+        }
+
+        test leap_day {
+            input: "2024-02-29 12:00:00",
+            result: Some(1_709_208_000),
+        }
+
+        test not_a_leap_day {
+            input: "2023-02-29 12:00:00",
+            result: None,
+        }
+
+        test before_epoch {
+            input: "1969-12-31 23:59:59",
+            result: None,
+        }
+
+        test invalid_time {
+            input: "2022-05-27 24:00:00",
+            result: None,
+        }
+
+        test missing_time {
+            input: "2022-05-27",
+            result: None,
+        }
+
+        test last_supported_year {
+            input: "9999-12-31 23:59:59",
+            result: Some(253_402_300_799),
+        }
+
+        test year_out_of_range {
+            input: "10000-01-01 00:00:00",
+            result: None,
+        }
+
+        test month_out_of_range {
+            input: "2022-13-01 00:00:00",
+            result: None,
+        }
+
+        test day_out_of_range {
+            input: "2022-01-9223372036854775807 00:00:00",
+            result: None,
+        }
+
+        test time_out_of_range {
+            input: "2022-05-27 18446744073709551615:00:00",
+            result: None,
+        }
+    }
+
+    #[test]
+    fn write_date_round_trip() {
+        for timestamp in [0, 951_782_400, 1_653_668_027, 1_709_208_000, 4_102_444_799] {
+            let mut output = String::new();
+            write_date(&mut output, timestamp).unwrap();
+
+            assert_eq!(parse_date(&output), Some(timestamp), "{output}");
+        }
+    }
+}
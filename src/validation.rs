@@ -0,0 +1,394 @@
+use core::ops::RangeInclusive;
+
+use crate::{Acceleration, BatteryPotential, Field, Humidity, Pressure, Temperature};
+
+/// Ranges of plausible measurements, used for detecting corrupted readings
+///
+/// The default limits cover the operating ranges of the sensors used in a RuuviTag: BME280 and
+/// SHTC3 for temperature, humidity and pressure, LIS2DH12 for acceleration, and the supported
+/// battery potential range.
+///
+/// # Examples
+///
+/// ```rust
+/// use ruuvi_sensor_protocol::{Field, PlausibilityLimits, SensorValues};
+/// # use ruuvi_sensor_protocol::ParseError;
+///
+/// let id = 0x0499;
+/// let value = [
+///     0x05, 0x7F, 0xFF, 0x53, 0x94, 0xC3, 0x7C, 0x00, 0x04, 0xFF, 0xFC, 0x04, 0x0C, 0xAC, 0x36,
+///     0x42, 0x00, 0xCD, 0xCB, 0xB8, 0x33, 0x4C, 0x88, 0x4F,
+/// ];
+/// let values = SensorValues::from_manufacturer_specific_data(id, value)?;
+/// let report = PlausibilityLimits::default().validate(&values);
+///
+/// assert!(!report.is_plausible());
+/// assert_eq!(report.get(Field::Temperature).map(|value| value.value), Some(163_835));
+/// # Ok::<(), ParseError>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlausibilityLimits {
+    /// temperature in milli-Celsius
+    pub temperature: RangeInclusive<i32>,
+    /// relative humidity in parts per million
+    pub humidity: RangeInclusive<u32>,
+    /// pressure in pascals
+    pub pressure: PressureLimits,
+    /// largest absolute value of each acceleration component in milli-G
+    pub acceleration: u16,
+    /// battery potential in milli-volts
+    pub battery_potential: RangeInclusive<u16>,
+}
+
+impl PlausibilityLimits {
+    /// Returns limits for a tag with BME280 environmental sensor, which measures temperature
+    /// between -40 °C and 85 °C and pressure between 300 hPa and 1100 hPa.
+    #[must_use]
+    pub fn bme280() -> Self {
+        Self {
+            temperature: -40_000..=85_000,
+            ..Self::default()
+        }
+    }
+
+    /// Returns limits for a tag with SHTC3 environmental sensor, which measures temperature
+    /// between -40 °C and 125 °C. The sensor does not measure pressure, so any pressure reading
+    /// is considered implausible.
+    #[must_use]
+    pub fn shtc3() -> Self {
+        Self {
+            pressure: PressureLimits::Unsupported,
+            ..Self::default()
+        }
+    }
+
+    /// Validates available measurements against the limits. Unavailable measurements are not
+    /// reported.
+    pub fn validate<T>(&self, values: &T) -> PlausibilityReport
+    where
+        T: Acceleration + BatteryPotential + Humidity + Pressure + Temperature,
+    {
+        let mut report = PlausibilityReport::default();
+
+        if let Some(temperature) = values.temperature_as_millicelsius() {
+            report.check(Field::Temperature, temperature, Some(&self.temperature));
+        }
+
+        if let Some(humidity) = values.humidity_as_ppm() {
+            report.check(Field::Humidity, humidity, Some(&self.humidity));
+        }
+
+        if let Some(pressure) = values.pressure_as_pascals() {
+            let range = match &self.pressure {
+                PressureLimits::Range(range) => Some(range),
+                PressureLimits::Unsupported => None,
+            };
+            report.check(Field::Pressure, pressure, range);
+        }
+
+        if let Some(acceleration) = values.acceleration_vector_as_milli_g() {
+            let limit = i32::from(self.acceleration);
+            let components = [acceleration.0, acceleration.1, acceleration.2];
+            let largest = components
+                .into_iter()
+                .map(i32::from)
+                .max_by_key(|component| component.abs())
+                .unwrap_or_default();
+
+            report.check(Field::Acceleration, largest, Some(&(-limit..=limit)));
+        }
+
+        if let Some(potential) = values.battery_potential_as_millivolts() {
+            report.check(
+                Field::BatteryPotential,
+                potential,
+                Some(&self.battery_potential),
+            );
+        }
+
+        report
+    }
+}
+
+impl Default for PlausibilityLimits {
+    fn default() -> Self {
+        Self {
+            temperature: -40_000..=125_000,
+            humidity: 0..=1_000_000,
+            pressure: PressureLimits::Range(30_000..=110_000),
+            acceleration: 16_000,
+            battery_potential: 1_600..=3_600,
+        }
+    }
+}
+
+/// Plausible pressure measurements of [`PlausibilityLimits`]
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PressureLimits {
+    /// The sensor does not measure pressure, so any pressure reading is implausible
+    Unsupported,
+    /// Range of plausible pressure in pascals
+    Range(RangeInclusive<u32>),
+}
+
+/// A measurement which is outside of the plausible range
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ImplausibleValue {
+    /// Field of the measurement
+    pub field: Field,
+    /// Measured value in the unit of the field, saturated to the range of `i32`. For acceleration
+    /// this is the component with the largest absolute value.
+    pub value: i32,
+    /// Smallest plausible value, or `None` if the sensor does not measure the field
+    pub min: Option<i32>,
+    /// Largest plausible value, or `None` if the sensor does not measure the field
+    pub max: Option<i32>,
+}
+
+/// Result of validating a set of measurements with [`PlausibilityLimits`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlausibilityReport {
+    values: [Option<ImplausibleValue>; 5],
+}
+
+impl PlausibilityReport {
+    /// Returns `true` if all available measurements are within the limits
+    #[must_use]
+    pub fn is_plausible(&self) -> bool {
+        self.values.iter().all(Option::is_none)
+    }
+
+    /// Returns the implausible value of the `field` if it was reported
+    #[must_use]
+    pub fn get(&self, field: Field) -> Option<&ImplausibleValue> {
+        self.iter().find(|value| value.field == field)
+    }
+
+    /// Returns an iterator over the implausible values
+    pub fn iter(&self) -> impl Iterator<Item = &ImplausibleValue> {
+        self.values.iter().flatten()
+    }
+
+    /// Reports the value if it is outside of the range, or if there is no range because the field
+    /// is not measured
+    fn check<T>(&mut self, field: Field, value: T, range: Option<&RangeInclusive<T>>)
+    where
+        T: Copy + PartialOrd,
+        i32: TryFrom<T>,
+    {
+        if range.is_some_and(|range| range.contains(&value)) {
+            return;
+        }
+
+        let saturate = |value: T| i32::try_from(value).unwrap_or(i32::MAX);
+        let implausible = ImplausibleValue {
+            field,
+            value: saturate(value),
+            min: range.map(|range| saturate(*range.start())),
+            max: range.map(|range| saturate(*range.end())),
+        };
+
+        if let Some(slot) = self.values.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(implausible);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::AccelerationVector;
+
+    #[derive(Default)]
+    struct Values {
+        acceleration: Option<AccelerationVector>,
+        battery_potential: Option<u16>,
+        humidity: Option<u32>,
+        pressure: Option<u32>,
+        temperature: Option<i32>,
+    }
+
+    impl Acceleration for Values {
+        fn acceleration_vector_as_milli_g(&self) -> Option<AccelerationVector> {
+            self.acceleration
+        }
+    }
+
+    impl BatteryPotential for Values {
+        fn battery_potential_as_millivolts(&self) -> Option<u16> {
+            self.battery_potential
+        }
+    }
+
+    impl Humidity for Values {
+        fn humidity_as_ppm(&self) -> Option<u32> {
+            self.humidity
+        }
+    }
+
+    impl Pressure for Values {
+        fn pressure_as_pascals(&self) -> Option<u32> {
+            self.pressure
+        }
+    }
+
+    impl Temperature for Values {
+        fn temperature_as_millikelvins(&self) -> Option<u32> {
+            self.temperature
+                .and_then(|value| u32::try_from(value + 273_150).ok())
+        }
+    }
+
+    #[test]
+    fn report_has_default_traits() {
+        crate::testing::type_has_default_traits::<PlausibilityLimits>();
+        crate::testing::type_has_default_traits::<PressureLimits>();
+        crate::testing::type_has_default_traits::<PlausibilityReport>();
+    }
+
+    macro_rules! test_validate {
+        (
+            $(
+                test $name: ident {
+                    limits: $limits: expr,
+                    values: $values: expr,
+                    implausible: [$($field: expr => $value: expr),*],
+                }
+            )+
+        ) => {
+            $(
+                #[test]
+                fn $name() {
+                    let report = $limits.validate(&$values);
+                    let implausible: &[(Field, i32)] = &[$(($field, $value)),*];
+
+                    assert_eq!(report.is_plausible(), implausible.is_empty());
+                    assert!(
+                        report
+                            .iter()
+                            .map(|value| (value.field, value.value))
+                            .eq(implausible.iter().copied())
+                    );
+                }
+            )+
+        };
+    }
+
+    test_validate! {
+        test no_values {
+            limits: PlausibilityLimits::default(),
+            values: Values::default(),
+            implausible: [],
+        }
+
+        test plausible_values {
+            limits: PlausibilityLimits::default(),
+            values: Values {
+                acceleration: Some(AccelerationVector(4, -4, 1036)),
+                battery_potential: Some(2977),
+                humidity: Some(534_900),
+                pressure: Some(100_044),
+                temperature: Some(24_300),
+            },
+            implausible: [],
+        }
+
+        test implausible_values {
+            limits: PlausibilityLimits::default(),
+            values: Values {
+                acceleration: Some(AccelerationVector(4, -0x7FFF, 1036)),
+                battery_potential: Some(3_646),
+                humidity: Some(1_638_350),
+                pressure: Some(0),
+                temperature: Some(300_000),
+            },
+            implausible: [
+                Field::Temperature => 300_000,
+                Field::Humidity => 1_638_350,
+                Field::Pressure => 0,
+                Field::Acceleration => -0x7FFF,
+                Field::BatteryPotential => 3_646
+            ],
+        }
+
+        test limits_are_inclusive {
+            limits: PlausibilityLimits::default(),
+            values: Values {
+                acceleration: Some(AccelerationVector(16_000, -16_000, 0)),
+                battery_potential: Some(1_600),
+                humidity: Some(1_000_000),
+                pressure: Some(30_000),
+                temperature: Some(-40_000),
+            },
+            implausible: [],
+        }
+
+        test bme280_temperature {
+            limits: PlausibilityLimits::bme280(),
+            values: Values {
+                temperature: Some(100_000),
+                ..Values::default()
+            },
+            implausible: [Field::Temperature => 100_000],
+        }
+
+        test shtc3_temperature {
+            limits: PlausibilityLimits::shtc3(),
+            values: Values {
+                temperature: Some(100_000),
+                ..Values::default()
+            },
+            implausible: [],
+        }
+
+        test shtc3_pressure {
+            limits: PlausibilityLimits::shtc3(),
+            values: Values {
+                pressure: Some(100_044),
+                ..Values::default()
+            },
+            implausible: [Field::Pressure => 100_044],
+        }
+    }
+
+    #[test]
+    fn report_contains_limits() {
+        let values = Values {
+            battery_potential: Some(1_500),
+            ..Values::default()
+        };
+        let report = PlausibilityLimits::default().validate(&values);
+
+        assert_eq!(
+            report.get(Field::BatteryPotential),
+            Some(&ImplausibleValue {
+                field: Field::BatteryPotential,
+                value: 1_500,
+                min: Some(1_600),
+                max: Some(3_600),
+            })
+        );
+        assert_eq!(report.get(Field::Temperature), None);
+    }
+
+    #[test]
+    fn report_without_limits() {
+        let values = Values {
+            pressure: Some(100_044),
+            ..Values::default()
+        };
+        let report = PlausibilityLimits::shtc3().validate(&values);
+
+        assert_eq!(
+            report.get(Field::Pressure),
+            Some(&ImplausibleValue {
+                field: Field::Pressure,
+                value: 100_044,
+                min: None,
+                max: None,
+            })
+        );
+    }
+}
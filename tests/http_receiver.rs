@@ -0,0 +1,189 @@
+//! Tests of the gateway HTTP receiver, posting gateway payloads to a local server
+
+use std::io;
+
+use axum::Router;
+use ruuvi_sensor_protocol::{
+    gateway::{channel_receiver, receiver, History, HttpAuth},
+    Mac, MeasurementSequenceNumber, Temperature,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+
+/// Hand-written payload in the format posted by the gateway, not a capture of real traffic: covers
+/// a data format 5 tag next to a legacy data format 3 tag
+const PAYLOAD: &str = include_str!("../src/gateway/fixtures/http-post-edge-cases.json");
+const GATEWAY: Mac = Mac::new([0xC8, 0x25, 0x2D, 0x8E, 0x9C, 0x2C]);
+const TAG: Mac = Mac::new([0xF4, 0x1F, 0x0C, 0x28, 0xCB, 0xD6]);
+const LEGACY_TAG: Mac = Mac::new([0xC2, 0x5E, 0x1A, 0x4B, 0x7D, 0x90]);
+
+/// Serves the router on a random local port, returning the port
+async fn serve(router: Router) -> io::Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    Ok(port)
+}
+
+/// Sends a request like the gateway does, returning the response
+async fn request(
+    port: u16,
+    method: &str,
+    authorization: Option<&str>,
+    body: &str,
+) -> io::Result<String> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    let authorization = authorization
+        .map(|authorization| format!("Authorization: {authorization}\r\n"))
+        .unwrap_or_default();
+    let request = format!(
+        "{method} / HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n{authorization}\r\n{body}",
+        body.len()
+    );
+
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+
+    Ok(response)
+}
+
+fn status(response: &str) -> Option<&str> {
+    response.split(' ').nth(1)
+}
+
+fn assert_fixture(history: &History) {
+    assert_eq!(history.gateway, Some(GATEWAY));
+    assert_eq!(history.timestamp, Some(1_653_668_042));
+    assert_eq!(history.tags.len(), 2);
+
+    let readings = &history.tags[&TAG];
+    assert_eq!(readings[0].timestamp, 1_653_668_041);
+    assert_eq!(readings[0].rssi, Some(-26));
+    assert_eq!(
+        readings[0].values.measurement_sequence_number(),
+        Some(10_892)
+    );
+
+    let readings = &history.tags[&LEGACY_TAG];
+    assert_eq!(readings[0].values.temperature_as_millicelsius(), Some(1690));
+}
+
+#[tokio::test]
+async fn delivers_to_callback() -> io::Result<()> {
+    let (sender, received) = std::sync::mpsc::channel();
+    let router = receiver(&HttpAuth::None, move |history| {
+        let _ = sender.send(history);
+    });
+    let port = serve(router).await?;
+
+    let response = request(port, "POST", None, PAYLOAD).await?;
+
+    assert_eq!(status(&response), Some("200"));
+    let history = received.try_recv().ok();
+    assert!(history.is_some());
+    history.iter().for_each(assert_fixture);
+    Ok(())
+}
+
+#[tokio::test]
+async fn delivers_to_channel_with_bearer_auth() -> io::Result<()> {
+    let (sender, mut receiver) = mpsc::channel(1);
+    let port = serve(channel_receiver(&HttpAuth::Bearer("secret".into()), sender)).await?;
+
+    let response = request(port, "POST", Some("Bearer secret"), PAYLOAD).await?;
+
+    assert_eq!(status(&response), Some("200"));
+    let history = receiver.recv().await;
+    assert!(history.is_some());
+    history.iter().for_each(assert_fixture);
+    Ok(())
+}
+
+#[tokio::test]
+async fn checks_basic_auth() -> io::Result<()> {
+    let (sender, mut receiver) = mpsc::channel(1);
+    let auth = HttpAuth::Basic {
+        username: "ruuvi".into(),
+        password: "secret".into(),
+    };
+    let port = serve(channel_receiver(&auth, sender)).await?;
+
+    let missing = request(port, "POST", None, PAYLOAD).await?;
+    let wrong = request(port, "POST", Some("Basic cnV1dmk6d3Jvbmc="), PAYLOAD).await?;
+    let correct = request(port, "POST", Some("Basic cnV1dmk6c2VjcmV0"), PAYLOAD).await?;
+
+    assert_eq!(status(&missing), Some("401"));
+    assert!(missing
+        .to_ascii_lowercase()
+        .contains("www-authenticate: basic"));
+    assert_eq!(status(&wrong), Some("401"));
+    assert_eq!(status(&correct), Some("200"));
+    assert!(receiver.recv().await.is_some());
+    assert_eq!(receiver.try_recv().ok(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn delivers_valid_entries_of_partially_invalid_payload() -> io::Result<()> {
+    let (sender, mut receiver) = mpsc::channel(1);
+    let port = serve(channel_receiver(&HttpAuth::None, sender)).await?;
+    let payload = PAYLOAD.replace(
+        "02010611FF990403170145355803E804E705E60886",
+        "02010611FF4C00",
+    );
+
+    let response = request(port, "POST", None, &payload).await?;
+
+    assert_eq!(status(&response), Some("200"));
+    let history = receiver.recv().await;
+    assert_eq!(history.as_ref().map(|history| history.tags.len()), Some(1));
+    assert!(history
+        .as_ref()
+        .is_some_and(|history| history.tags.contains_key(&TAG)));
+    let invalid = history.map(|history| history.invalid).unwrap_or_default();
+    assert_eq!(invalid.len(), 1);
+    assert_eq!(invalid[0].tag, "C2:5E:1A:4B:7D:90");
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_payload() -> io::Result<()> {
+    let (sender, mut receiver) = mpsc::channel(1);
+    let port = serve(channel_receiver(&HttpAuth::None, sender)).await?;
+
+    let response = request(port, "POST", None, "{\"data\": {\"tags\": []}}").await?;
+
+    assert_eq!(status(&response), Some("400"));
+    assert!(response.contains("Invalid gateway payload"));
+    assert_eq!(receiver.try_recv().ok(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_other_methods() -> io::Result<()> {
+    let port = serve(receiver(&HttpAuth::None, |_| {})).await?;
+
+    let response = request(port, "GET", None, "").await?;
+
+    assert_eq!(status(&response), Some("405"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn reports_closed_channel() -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel(1);
+    drop(receiver);
+    let port = serve(channel_receiver(&HttpAuth::None, sender)).await?;
+
+    let response = request(port, "POST", None, PAYLOAD).await?;
+
+    assert_eq!(status(&response), Some("503"));
+    Ok(())
+}
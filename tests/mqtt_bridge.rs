@@ -0,0 +1,239 @@
+//! Tests of `ruuvi-mqtt-bridge` binary against an MQTT broker spawned in the test
+
+use core::{net::SocketAddr, time::Duration};
+use std::{collections::HashMap, io, process::Stdio, thread};
+
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
+use rumqttd::{Broker, Config, ConnectionSettings, RouterConfig, ServerSettings};
+use tokio::{
+    net::TcpStream,
+    process::{Child, Command},
+    time::{sleep, timeout},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const TOPIC: &str = "ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:D6";
+const PAYLOAD: &str = "{\"data\": \"02010611FF990403170145355803E804E705E60886\"}";
+
+/// Starts a broker on a free port and returns the port once the broker accepts connections
+async fn spawn_broker() -> io::Result<u16> {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+
+    let server = ServerSettings {
+        name: "v4".into(),
+        listen: address,
+        tls: None,
+        next_connection_delay_ms: 1,
+        connections: ConnectionSettings {
+            connection_timeout_ms: 10_000,
+            max_payload_size: 0x1_0000,
+            max_inflight_count: 100,
+            auth: None,
+            external_auth: None,
+            dynamic_filters: true,
+        },
+    };
+    let config = Config {
+        router: RouterConfig {
+            max_connections: 10,
+            max_outgoing_packet_count: 200,
+            max_segment_size: 0x10_0000,
+            max_segment_count: 10,
+            ..RouterConfig::default()
+        },
+        v4: Some(HashMap::from([("1".into(), server)])),
+        ..Config::default()
+    };
+    thread::spawn(move || {
+        if let Err(error) = Broker::new(config).start() {
+            eprintln!("Broker stopped: {error}");
+        }
+    });
+
+    timeout(TIMEOUT, async {
+        while TcpStream::connect(address).await.is_err() {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await?;
+
+    Ok(port)
+}
+
+fn spawn_bridge(port: u16, format: &str) -> io::Result<Child> {
+    Command::new(env!("CARGO_BIN_EXE_ruuvi-mqtt-bridge"))
+        .args(["--host", "127.0.0.1", "--port", &port.to_string()])
+        .args(["--client-id", format, "--format", format])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+}
+
+/// Connects to the broker and subscribes to the republished messages
+async fn subscribe(port: u16) -> (AsyncClient, EventLoop) {
+    let options = MqttOptions::new("test", "127.0.0.1", port);
+    let (client, mut event_loop) = AsyncClient::new(options, 100);
+
+    let result = timeout(TIMEOUT, async {
+        loop {
+            match event_loop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    let _ = client.try_subscribe("ruuvi-bridge/#", QoS::AtMostOnce);
+                }
+                Ok(Event::Incoming(Packet::SubAck(_))) => break,
+                _ => {}
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(result, Ok(()), "could not subscribe in time");
+    (client, event_loop)
+}
+
+/// Adds republished messages received within a short while to `bridged`
+async fn receive(event_loop: &mut EventLoop, bridged: &mut Vec<(String, String)>) {
+    let _ = timeout(Duration::from_millis(200), async {
+        loop {
+            if let Ok(Event::Incoming(Packet::Publish(publish))) = event_loop.poll().await {
+                let payload = String::from_utf8_lossy(&publish.payload).into_owned();
+                bridged.push((publish.topic, payload));
+            }
+        }
+    })
+    .await;
+}
+
+/// Publishes the messages until the bridge republishes something, since the bridge may not have
+/// subscribed yet, and returns the topics and payloads republished within a short while after
+async fn bridged_messages(port: u16, messages: &[(&str, &str)]) -> Vec<(String, String)> {
+    let (client, mut event_loop) = subscribe(port).await;
+    let mut bridged = Vec::new();
+
+    let result = timeout(TIMEOUT, async {
+        while bridged.is_empty() {
+            for (topic, payload) in messages {
+                let _ = client.try_publish(*topic, QoS::AtMostOnce, false, *payload);
+            }
+
+            receive(&mut event_loop, &mut bridged).await;
+        }
+    })
+    .await;
+
+    assert_eq!(result, Ok(()), "bridge did not republish messages in time");
+    bridged.sort();
+    bridged.dedup();
+    bridged
+}
+
+#[tokio::test]
+async fn bridges_json() -> io::Result<()> {
+    let port = spawn_broker().await?;
+    let _bridge = spawn_bridge(port, "json")?;
+
+    let bridged = bridged_messages(port, &[(TOPIC, PAYLOAD)]).await;
+
+    assert_eq!(bridged.len(), 1);
+    assert_eq!(bridged[0].0, "ruuvi-bridge/F4:1F:0C:28:CB:D6");
+    let payload: serde_json::Value = serde_json::from_str(&bridged[0].1)?;
+    assert_eq!(payload["temperature"], 1.69);
+    assert_eq!(payload["pressure"], 63_656);
+    assert_eq!(payload["gw_mac"], "C8:25:2D:8E:9C:2C");
+    Ok(())
+}
+
+#[tokio::test]
+async fn bridges_influx_lines() -> io::Result<()> {
+    let port = spawn_broker().await?;
+    let _bridge = spawn_bridge(port, "influx")?;
+
+    let bridged = bridged_messages(port, &[(TOPIC, PAYLOAD)]).await;
+
+    assert_eq!(bridged.len(), 1);
+    assert!(bridged[0]
+        .1
+        .starts_with("ruuvi,mac=F4:1F:0C:28:CB:D6,gw_mac=C8:25:2D:8E:9C:2C dataFormat=3i,"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn bridges_fields() -> io::Result<()> {
+    let port = spawn_broker().await?;
+    let _bridge = spawn_bridge(port, "fields")?;
+
+    let bridged = bridged_messages(port, &[(TOPIC, PAYLOAD)]).await;
+
+    assert!(bridged.contains(&(
+        "ruuvi-bridge/F4:1F:0C:28:CB:D6/temperature".into(),
+        "1.69".into()
+    )));
+    assert!(bridged.contains(&(
+        "ruuvi-bridge/F4:1F:0C:28:CB:D6/pressure".into(),
+        "63656".into()
+    )));
+    Ok(())
+}
+
+#[tokio::test]
+async fn skips_invalid_messages() -> io::Result<()> {
+    let port = spawn_broker().await?;
+    let _bridge = spawn_bridge(port, "json")?;
+
+    let bridged = bridged_messages(
+        port,
+        &[
+            (TOPIC, "{\"data\": \"invalid\"}"),
+            (
+                "ruuvi/C8:25:2D:8E:9C:2C/gw_status",
+                "{\"state\": \"online\"}",
+            ),
+            ("ruuvi/C8:25:2D:8E:9C:2C/D6:CB:28:0C:1F:F4", PAYLOAD),
+        ],
+    )
+    .await;
+
+    assert_eq!(bridged.len(), 1);
+    assert_eq!(bridged[0].0, "ruuvi-bridge/D6:CB:28:0C:1F:F4");
+    Ok(())
+}
+
+#[tokio::test]
+async fn bridges_bursts() -> io::Result<()> {
+    const BURST: u8 = 50;
+
+    let port = spawn_broker().await?;
+    let _bridge = spawn_bridge(port, "fields")?;
+    bridged_messages(port, &[(TOPIC, PAYLOAD)]).await;
+
+    let (client, mut event_loop) = subscribe(port).await;
+    for tag in 0..BURST {
+        let topic = format!("ruuvi/C8:25:2D:8E:9C:2C/F4:1F:0C:28:CB:{tag:02X}");
+        let result = client.publish(topic, QoS::AtMostOnce, false, PAYLOAD).await;
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    let mut bridged = Vec::new();
+    let temperature = |(topic, _): &&(String, String)| topic.ends_with("/temperature");
+    let result = timeout(TIMEOUT, async {
+        while bridged.iter().filter(temperature).count() < usize::from(BURST) {
+            receive(&mut event_loop, &mut bridged).await;
+        }
+    })
+    .await;
+
+    assert_eq!(
+        result,
+        Ok(()),
+        "bridge did not republish the whole burst in time"
+    );
+    assert_eq!(
+        bridged.iter().filter(temperature).count(),
+        usize::from(BURST)
+    );
+    Ok(())
+}